@@ -0,0 +1,583 @@
+use crate::commands::accounts;
+use crate::commands::backups;
+use crate::commands::modpacks;
+use crate::commands::settings::{self, LauncherSettings, Settings, SETTINGS_STORE};
+use crate::logging;
+use crate::types::ManifestSeverity;
+use crate::utils::download::{DownloadControl, LaunchGuard};
+use crate::utils::manifest;
+use crate::utils::redact;
+use crate::utils::sessions;
+use crate::utils::sync::SyncEvent;
+use crate::utils::vault::{self, VaultPaths, VaultState, VAULT_PASSWORD};
+use lighty_launcher::event::{ConsoleStream, Event, EventBus, LaunchEvent};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use tokio::sync::{mpsc, oneshot};
+
+/// Identifiant de l'application (`identifier` de `tauri.conf.json`) : sans app Tauri,
+/// les dossiers de données (vault, settings) sont résolus comme le fait Tauri.
+const APP_IDENTIFIER: &str = "fr.miratopia.minecraft-launcher";
+
+/// Codes de sortie des sous-commandes
+const EXIT_OK: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+/// Interrompu par Ctrl+C (convention des shells : 128 + SIGINT)
+const EXIT_CANCELLED: i32 = 130;
+
+/// Aide affichée pour une sous-commande inconnue ou mal utilisée
+const USAGE: &str = "Usage:
+  miratopia_launcher list [--account <name>]
+  miratopia_launcher install <modpack>
+  miratopia_launcher launch <modpack> [--account <name>]
+  miratopia_launcher verify [<modpack>...]
+  miratopia_launcher accounts list
+  miratopia_launcher accounts add-offline <name>
+  miratopia_launcher validate-manifest <file|url|modpack> [--check-urls] [--json]
+
+`install` only fetches the modpack files (mods, configs); the loader, Java,
+libraries and assets are downloaded by the first `launch`.
+
+Exit codes: 0 success, 1 failure, 2 usage error, 130 interrupted";
+
+/// Exécute une sous-commande du launcher sans ouvrir de fenêtre (ni webview, ni affichage
+/// requis) : mêmes modules que l'interface pour les modpacks, les settings et le vault.
+///
+/// Retourne le code de sortie, ou `None` si aucun argument ne correspond à une sous-commande
+/// (lancement normal de l'interface).
+///
+/// Sous Windows, seuls les builds avec la feature `console` ont une sortie visible.
+pub async fn run(args: &[String]) -> Option<i32> {
+    let (command, args) = args.split_first()?;
+    let code = match command.as_str() {
+        "list" => list(args).await,
+        "install" => install(args).await,
+        "launch" => launch(args).await,
+        "verify" => verify(args).await,
+        "accounts" => accounts_command(args).await,
+        "validate-manifest" => validate_manifest(args).await,
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Arguments d'une sous-commande : valeurs positionnelles et `--account <name>`
+struct CommandArgs {
+    positional: Vec<String>,
+    account: Option<String>,
+}
+
+fn parse_args(args: &[String], accepts_account: bool) -> Result<CommandArgs, String> {
+    let mut positional = Vec::new();
+    let mut account = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--account" if accepts_account => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| "--account requires a name".to_string())?;
+                account = Some(name.clone());
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unexpected argument '{}'", arg)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+    Ok(CommandArgs {
+        positional,
+        account,
+    })
+}
+
+/// Un seul argument positionnel attendu (nom du modpack, du compte)
+fn single(args: &CommandArgs, what: &str) -> Result<String, String> {
+    match args.positional.as_slice() {
+        [value] => Ok(value.clone()),
+        [] => Err(format!("Missing {}", what)),
+        _ => Err(format!("Expected a single {}", what)),
+    }
+}
+
+fn usage_error(e: &str) -> i32 {
+    eprintln!("{}\n\n{}", e, USAGE);
+    EXIT_USAGE
+}
+
+/// `app_data_dir` de Tauri : vault et `settings.json`
+fn app_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Cannot resolve the data directory".to_string())
+}
+
+/// `app_local_data_dir` de Tauri : sel du vault
+fn app_local_data_dir() -> Result<PathBuf, String> {
+    dirs::data_local_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Cannot resolve the local data directory".to_string())
+}
+
+/// Settings de l'interface, appliqués comme au démarrage de l'app (log, réseau)
+fn load_settings() -> Result<(LauncherSettings, HashMap<String, Settings>), String> {
+    let (launcher, modpacks) = settings::read_settings_file(&app_data_dir()?.join(SETTINGS_STORE));
+    if !logging::is_env_override() {
+        if let Some(level) = launcher.log_level.as_deref() {
+            let _ = logging::set_log_level(level);
+        }
+    }
+    settings::apply_network_config(&launcher);
+    Ok((launcher, modpacks))
+}
+
+/// Ouvre le vault des comptes partagé avec l'interface
+fn open_vault() -> Result<VaultState, String> {
+    let state = VaultState::default();
+    let paths = VaultPaths::new(&app_data_dir()?, &app_local_data_dir()?);
+    vault::init_vault(paths, &state, VAULT_PASSWORD)?;
+    Ok(state)
+}
+
+/// Compte demandé par `--account`, sinon le compte actif de l'interface
+fn account_name(state: &VaultState, account: Option<String>) -> Result<String, String> {
+    match account {
+        Some(name) => {
+            if !accounts::account_names(state)?.contains(&name) {
+                return Err(format!("Unknown account '{}'", name));
+            }
+            Ok(name)
+        }
+        None => accounts::active_account_name(state)?
+            .ok_or_else(|| "No account, add one with `accounts add-offline <name>`".to_string()),
+    }
+}
+
+/// Ctrl+C annule le téléchargement en cours (fichiers partiels nettoyés, code 130)
+fn cancel_on_ctrl_c(control: &DownloadControl) {
+    let control = control.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("\nCancelling...");
+            control.cancel();
+        }
+    });
+}
+
+/// Affiche la progression de la synchronisation des fichiers (sur stderr)
+fn spawn_sync_printer(modpack_name: &str) -> mpsc::UnboundedSender<SyncEvent> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let modpack_name = modpack_name.to_string();
+    tokio::spawn(async move {
+        let mut total: u64 = 0;
+        let mut downloaded: u64 = 0;
+        let mut last_percent = None;
+        while let Some(event) = rx.recv().await {
+            match event {
+                SyncEvent::Started {
+                    total_bytes,
+                    total_files,
+                } => {
+                    total = total_bytes;
+                    if total_files > 0 {
+                        eprintln!(
+                            "[{}] Downloading {} file(s), {:.1} MB",
+                            modpack_name,
+                            total_files,
+                            total_bytes as f64 / 1_000_000.0
+                        );
+                    }
+                }
+                SyncEvent::Progress { bytes } => {
+                    downloaded += bytes;
+                    let percent = (downloaded * 100)
+                        .checked_div(total)
+                        .unwrap_or(100)
+                        .min(100);
+                    if last_percent != Some(percent) {
+                        last_percent = Some(percent);
+                        eprint!("\r[{}] {:>3}%", modpack_name, percent);
+                        let _ = std::io::stderr().flush();
+                    }
+                }
+                SyncEvent::Completed { downloaded_files } => {
+                    if last_percent.is_some() {
+                        eprintln!();
+                    }
+                    eprintln!(
+                        "[{}] Files up to date ({} downloaded)",
+                        modpack_name, downloaded_files
+                    );
+                }
+            }
+        }
+    });
+    tx
+}
+
+/// `list` : modpacks accessibles au compte, avec ceux déjà installés
+async fn list(args: &[String]) -> i32 {
+    let args = match parse_args(args, true) {
+        Ok(args) if args.positional.is_empty() => args,
+        Ok(_) => return usage_error("`list` takes no positional argument"),
+        Err(e) => return usage_error(&e),
+    };
+    let result = async {
+        load_settings()?;
+        let state = open_vault()?;
+        let account = account_name(&state, args.account)?;
+        modpacks::allowed_modpacks(&account).await
+    }
+    .await;
+
+    match result {
+        Ok(names) => {
+            let installed = modpacks::installed_modpack_names();
+            for name in names {
+                if installed.contains(&name) {
+                    println!("{}\tinstalled", name);
+                } else {
+                    println!("{}", name);
+                }
+            }
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// `install <modpack>` : fichiers du manifest téléchargés et vérifiés, sans lancer le jeu.
+///
+/// Lighty n'installe le loader, Java, les librairies et les assets qu'au lancement : ils sont
+/// téléchargés par le premier `launch`.
+async fn install(args: &[String]) -> i32 {
+    let modpack_name = match parse_args(args, false).and_then(|args| single(&args, "modpack")) {
+        Ok(name) => name,
+        Err(e) => return usage_error(&e),
+    };
+
+    let launch = match LaunchGuard::new(&modpack_name) {
+        Ok(launch) => launch,
+        Err(e) => return finish(Err(e), false),
+    };
+    cancel_on_ctrl_c(launch.control());
+
+    let result = async {
+        let (launcher_settings, _) = load_settings()?;
+        let retention = launcher_settings
+            .backup_retention
+            .unwrap_or(backups::DEFAULT_BACKUP_RETENTION);
+        modpacks::install_modpack_files(
+            &modpack_name,
+            launch.control(),
+            retention,
+            spawn_sync_printer(&modpack_name),
+        )
+        .await
+    }
+    .await;
+
+    finish(
+        result.map(|_| format!("Modpack '{}' installed", modpack_name)),
+        launch.control().is_cancelled(),
+    )
+}
+
+/// `launch <modpack>` : synchronise, lance le jeu, affiche sa console et sort avec son code
+async fn launch(args: &[String]) -> i32 {
+    let (modpack_name, account) = match parse_args(args, true)
+        .and_then(|args| Ok((single(&args, "modpack")?, args.account)))
+    {
+        Ok(parsed) => parsed,
+        Err(e) => return usage_error(&e),
+    };
+
+    let launch = match LaunchGuard::new(&modpack_name) {
+        Ok(launch) => launch,
+        Err(e) => return finish(Err(e), false),
+    };
+    cancel_on_ctrl_c(launch.control());
+
+    let event_bus = EventBus::new(1000);
+    let exit_code = spawn_game_printer(&event_bus);
+
+    let result = async {
+        let (launcher_settings, modpack_settings) = load_settings()?;
+        let state = open_vault()?;
+        let account = account_name(&state, account)?;
+        let profile = accounts::get_account(&state, &account)
+            .await?
+            .ok_or_else(|| format!("Account '{}' is incomplete, add it again", account))?;
+        modpacks::ensure_modpack_allowed(&profile.username, &modpack_name).await?;
+        eprintln!("Launching '{}' as {}", modpack_name, profile.username);
+
+        let retention = launcher_settings
+            .backup_retention
+            .unwrap_or(backups::DEFAULT_BACKUP_RETENTION);
+        modpacks::launch_modpack(
+            &modpack_name,
+            &profile,
+            modpack_settings
+                .get(&modpack_name)
+                .cloned()
+                .unwrap_or_default(),
+            retention,
+            &event_bus,
+            spawn_sync_printer(&modpack_name),
+            launch.control(),
+        )
+        .await
+    }
+    .await;
+
+    if let Err(e) = result {
+        return finish(Err(e), launch.control().is_cancelled());
+    }
+    // Le jeu est lancé : plus rien à annuler
+    drop(launch);
+
+    // Le code de sortie du jeu devient celui du launcher
+    match exit_code.await {
+        Ok(Some(code)) => {
+            eprintln!("Game exited with code {}", code);
+            code
+        }
+        _ => {
+            eprintln!("Game exited without exit code");
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// Affiche l'installation de lighty et la console du jeu ; retourne le code de sortie du jeu
+fn spawn_game_printer(event_bus: &EventBus) -> oneshot::Receiver<Option<i32>> {
+    let (tx, rx) = oneshot::channel();
+    let mut tx = Some(tx);
+    let mut receiver = event_bus.subscribe();
+    tokio::spawn(async move {
+        let mut total: u64 = 0;
+        let mut downloaded: u64 = 0;
+        let mut last_percent = None;
+        while let Ok(event) = receiver.next().await {
+            match event {
+                Event::Launch(LaunchEvent::InstallStarted {
+                    version,
+                    total_bytes,
+                }) => {
+                    total = total_bytes;
+                    downloaded = 0;
+                    last_percent = None;
+                    eprintln!(
+                        "Installing {} ({:.1} MB)",
+                        version,
+                        total_bytes as f64 / 1_000_000.0
+                    );
+                }
+                Event::Launch(LaunchEvent::InstallProgress { bytes }) => {
+                    downloaded += bytes;
+                    let percent = (downloaded * 100)
+                        .checked_div(total)
+                        .unwrap_or(100)
+                        .min(100);
+                    if last_percent != Some(percent) {
+                        last_percent = Some(percent);
+                        eprint!("\rInstalling {:>3}%", percent);
+                        let _ = std::io::stderr().flush();
+                    }
+                }
+                Event::Launch(LaunchEvent::InstallCompleted { version, .. }) => {
+                    eprintln!("\rInstalled {}", version);
+                }
+                Event::Launch(LaunchEvent::Launched { version, pid }) => {
+                    eprintln!("Game {} started (pid {})", version, pid);
+                }
+                Event::ConsoleOutput(e) => match e.stream {
+                    ConsoleStream::Stdout => println!("{}", redact::redact(&e.line)),
+                    ConsoleStream::Stderr => eprintln!("{}", redact::redact(&e.line)),
+                },
+                Event::InstanceLaunched(e) => sessions::start(&e.instance_name),
+                Event::InstanceExited(e) => {
+                    sessions::finish(&e.instance_name, e.exit_code);
+                    if let Some(tx) = tx.take() {
+                        let _ = tx.send(e.exit_code);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+    rx
+}
+
+/// `verify [<modpack>...]` : compare les instances installées (toutes par défaut) à leur
+/// manifest installé, sans réseau. Échec si un fichier manque ou a changé.
+async fn verify(args: &[String]) -> i32 {
+    let names = match parse_args(args, false) {
+        Ok(args) if args.positional.is_empty() => modpacks::installed_modpack_names(),
+        Ok(args) => args.positional,
+        Err(e) => return usage_error(&e),
+    };
+    if names.is_empty() {
+        println!("No modpack installed");
+        return EXIT_OK;
+    }
+
+    let mut code = EXIT_OK;
+    for name in names {
+        match modpacks::verify_modpack(&name).await {
+            Ok(mismatches) if mismatches.is_empty() => println!("{}: ok", name),
+            Ok(mismatches) => {
+                println!("{}: {} file(s) missing or modified", name, mismatches.len());
+                for path in mismatches {
+                    println!("  {}", path);
+                }
+                code = EXIT_FAILURE;
+            }
+            Err(e) => {
+                eprintln!("{}: {}", name, e);
+                code = EXIT_FAILURE;
+            }
+        }
+    }
+    code
+}
+
+/// `accounts list` / `accounts add-offline <name>`
+async fn accounts_command(args: &[String]) -> i32 {
+    let Some((action, args)) = args.split_first() else {
+        return usage_error("Missing accounts action");
+    };
+    let result = match action.as_str() {
+        "list" if args.is_empty() => open_vault().and_then(|state| {
+            let active = accounts::active_account_name(&state)?;
+            for name in accounts::account_names(&state)? {
+                if Some(&name) == active.as_ref() {
+                    println!("{}\tactive", name);
+                } else {
+                    println!("{}", name);
+                }
+            }
+            Ok(())
+        }),
+        "add-offline" => {
+            let name = match parse_args(args, false).and_then(|args| single(&args, "account name"))
+            {
+                Ok(name) => name,
+                Err(e) => return usage_error(&e),
+            };
+            add_offline_account(name).await
+        }
+        _ => return usage_error(&format!("Unknown accounts action '{}'", action)),
+    };
+
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("{}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+async fn add_offline_account(name: String) -> Result<(), String> {
+    let state = open_vault()?;
+    let profile = accounts::login_offline(None, name.clone()).await?;
+    accounts::store_account(&state, &name, &profile)?;
+    println!("Offline account '{}' added", name);
+    Ok(())
+}
+
+/// Affiche le résultat d'une installation ou d'un lancement et retourne son code de sortie
+fn finish(result: Result<String, String>, cancelled: bool) -> i32 {
+    match result {
+        Ok(message) => {
+            eprintln!("{}", message);
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            if cancelled {
+                EXIT_CANCELLED
+            } else {
+                EXIT_FAILURE
+            }
+        }
+    }
+}
+
+/// `validate-manifest` : échec si le manifest contient des erreurs, erreur d'usage
+/// s'il n'a pas pu être chargé
+async fn validate_manifest(args: &[String]) -> i32 {
+    let mut source = None;
+    let mut check_urls = false;
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check-urls" => check_urls = true,
+            "--json" => json = true,
+            _ if source.is_none() && !arg.starts_with("--") => source = Some(arg.as_str()),
+            _ => {
+                eprintln!("Unexpected argument '{}'\n{}", arg, USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+    let Some(source) = source else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    let value = match manifest::load_manifest(source).await {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_USAGE;
+        }
+    };
+    let report = if check_urls {
+        manifest::validate_manifest_with_urls(&value).await
+    } else {
+        manifest::validate_manifest(&value)
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("{}", e);
+                return EXIT_USAGE;
+            }
+        }
+    } else {
+        for issue in &report.issues {
+            let severity = match issue.severity {
+                ManifestSeverity::Error => "error",
+                ManifestSeverity::Warning => "warning",
+            };
+            match &issue.entry {
+                Some(entry) => println!("{:<7} {}: {}", severity, entry, issue.message),
+                None => println!("{:<7} {}", severity, issue.message),
+            }
+        }
+        println!(
+            "{} (schema v{}): {} error(s), {} warning(s)",
+            if report.valid { "valid" } else { "invalid" },
+            report.schema_version,
+            report.error_count,
+            report.warning_count
+        );
+    }
+
+    if report.valid {
+        EXIT_OK
+    } else {
+        EXIT_FAILURE
+    }
+}
@@ -1,3 +1,5 @@
+use crate::runners::tray;
+use crate::utils::redact;
 use crate::utils::vault::{commit_snapshot, with_sh, VaultState};
 use chrono::{DateTime, Utc};
 use lighty_auth::{microsoft::MicrosoftRefresh, offline::OfflineRefresh, AuthProvider};
@@ -52,6 +54,7 @@ pub async fn display_active_account(
 
 #[tauri::command]
 pub fn switch_active_account(
+    app_handle: AppHandle,
     state: State<'_, VaultState>,
     profile_name: &str,
 ) -> Result<(), String> {
@@ -79,6 +82,7 @@ pub fn switch_active_account(
     )?;
     // Persist to disk
     commit_snapshot(&state)?;
+    tray::rebuild_menu(&app_handle);
     Ok(())
 }
 
@@ -119,9 +123,17 @@ pub async fn display_account(
 pub async fn get_active_account(
     state: State<'_, VaultState>,
 ) -> Result<Option<UserProfile>, String> {
+    match active_account_name(&state)? {
+        Some(name) => get_account(&state, &name).await,
+        None => Ok(None),
+    }
+}
+
+/// Nom du compte actif, ou à défaut le premier de la liste
+pub fn active_account_name(state: &VaultState) -> Result<Option<String>, String> {
     // Lire le nom du compte actif depuis le stronghold
     let active_profile = with_sh(
-        &state,
+        state,
         |sh: &tauri_plugin_stronghold::stronghold::Stronghold| {
             let metadata_client = sh
                 .get_client(b"metadata/active_account")
@@ -133,7 +145,7 @@ pub async fn get_active_account(
                 .map_err(|e| e.to_string())?;
             match &name {
                 Some(bytes) => {
-                    tracing::info!("Valeur brute active_account lue: {:?}", bytes);
+                    tracing::debug!("Compte actif lu depuis le stronghold");
                     String::from_utf8(bytes.clone()).map_err(|e| e.to_string())
                 }
                 None => {
@@ -145,23 +157,18 @@ pub async fn get_active_account(
     )?;
     if active_profile.is_empty() {
         // Si aucun compte actif, on prend le premier de la liste
-        let accounts = list_accounts(state.clone()).await?;
-        if let Some(first) = accounts.first() {
-            return get_account(state, first).await;
-        } else {
-            return Ok(None);
-        }
+        return Ok(account_names(state)?.into_iter().next());
     }
-    get_account(state, &active_profile).await
+    Ok(Some(active_profile))
 }
 
 pub async fn get_account(
-    state: State<'_, VaultState>,
+    state: &VaultState,
     profile_name: &str,
 ) -> Result<Option<UserProfile>, String> {
     // Partie synchrone : lecture du profil depuis le vault
     let profile = with_sh(
-        &state,
+        state,
         |sh: &tauri_plugin_stronghold::stronghold::Stronghold| {
             let client_path = format!("minecraft/{}", profile_name);
             let client = sh
@@ -243,6 +250,7 @@ pub async fn get_account(
         Some(p) => p,
         None => return Ok(None),
     };
+    register_profile_secrets(&profile);
     // Partie asynchrone : refresh du token
     if let Some(refresh) = profile.refresh_impl.as_ref() {
         profile = refresh
@@ -252,9 +260,20 @@ pub async fn get_account(
     } else {
         return Err("No refresh implementation (cannot refresh token)".to_string());
     }
+    register_profile_secrets(&profile);
     Ok(Some(profile))
 }
 
+/// Enregistre les tokens du profil pour qu'ils soient masqués dans les logs et la console
+fn register_profile_secrets(profile: &UserProfile) {
+    if let Some(token) = profile.access_token.as_deref() {
+        redact::register_secret(token);
+    }
+    if let Some(token) = profile.refresh_token.as_deref() {
+        redact::register_secret(token);
+    }
+}
+
 #[tauri::command]
 pub async fn add_account(
     app_handle: AppHandle,
@@ -271,7 +290,7 @@ pub async fn add_account(
         }
         "offline" => {
             profile = login_offline(
-                event_bus,
+                Some(event_bus.inner()),
                 profile_name.clone().ok_or("Profile name is required")?,
             )
             .await?;
@@ -281,10 +300,21 @@ pub async fn add_account(
         }
     }
 
+    register_profile_secrets(&profile);
     let path_name = profile_name.as_ref().unwrap_or_else(|| &profile.username);
+    store_account(&state, path_name, &profile)?;
+    tray::rebuild_menu(&app_handle);
+    Ok(())
+}
 
+/// Enregistre un profil dans le vault sous `path_name` et l'ajoute à la liste des comptes
+pub fn store_account(
+    state: &VaultState,
+    path_name: &str,
+    profile: &UserProfile,
+) -> Result<(), String> {
     with_sh(
-        &state,
+        state,
         |sh: &tauri_plugin_stronghold::stronghold::Stronghold| {
             let client_path = format!("minecraft/{}", path_name);
             let client = sh
@@ -373,8 +403,8 @@ pub async fn add_account(
                     Some(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
                     None => Vec::new(),
                 };
-            if !accounts.contains(&path_name) {
-                accounts.push(path_name.clone());
+            if !accounts.iter().any(|account| account == path_name) {
+                accounts.push(path_name.to_string());
             }
             tracing::info!("Saving accounts list: {:?}", accounts);
             metadata_store
@@ -398,14 +428,18 @@ pub async fn add_account(
 
     // Persist to disk
     tracing::info!("Committing snapshot to disk for account: {}", path_name);
-    commit_snapshot(&state)?;
+    commit_snapshot(state)?;
     tracing::info!("Account {} saved successfully", path_name);
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn del_account(state: State<'_, VaultState>, profile_name: &str) -> Result<(), String> {
+pub fn del_account(
+    app_handle: AppHandle,
+    state: State<'_, VaultState>,
+    profile_name: &str,
+) -> Result<(), String> {
     with_sh(
         &state,
         |sh: &tauri_plugin_stronghold::stronghold::Stronghold| {
@@ -444,11 +478,17 @@ pub fn del_account(state: State<'_, VaultState>, profile_name: &str) -> Result<(
         },
     )?;
     commit_snapshot(&state)?;
+    tray::rebuild_menu(&app_handle);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn list_accounts(state: State<'_, VaultState>) -> Result<Vec<String>, String> {
+    account_names(&state)
+}
+
+/// Noms des comptes enregistrés dans le vault
+pub fn account_names(state: &VaultState) -> Result<Vec<String>, String> {
     let guard = state.inner.lock().unwrap();
     let sh: &tauri_plugin_stronghold::stronghold::Stronghold = match guard.as_ref() {
         Some(sh) => sh,
@@ -488,11 +528,14 @@ pub async fn list_accounts(state: State<'_, VaultState>) -> Result<Vec<String>,
 
 /// Supprime tous les comptes du vault et réinitialise les métadonnées.
 #[tauri::command]
-pub async fn clear_all_accounts(state: State<'_, VaultState>) -> Result<(), String> {
+pub async fn clear_all_accounts(
+    app_handle: AppHandle,
+    state: State<'_, VaultState>,
+) -> Result<(), String> {
     let accounts = list_accounts(state.clone()).await?;
 
     for account in &accounts {
-        if let Err(e) = del_account(state.clone(), account) {
+        if let Err(e) = del_account(app_handle.clone(), state.clone(), account) {
             tracing::warn!("Failed to delete account '{}': {}", account, e);
         }
     }
@@ -513,6 +556,7 @@ pub async fn clear_all_accounts(state: State<'_, VaultState>) -> Result<(), Stri
     )?;
 
     commit_snapshot(&state)?;
+    tray::rebuild_menu(&app_handle);
     tracing::info!("All accounts cleared ({} removed)", accounts.len());
     Ok(())
 }
@@ -523,13 +567,13 @@ pub async fn clear_all_accounts(state: State<'_, VaultState>) -> Result<(), Stri
 /// - `username`: The username to login with (should not be empty)
 /// # Returns
 /// A `UserProfile` containing the authenticated user's information, or an error message if authentication fails
-async fn login_offline(
-    event_bus: State<'_, EventBus>,
+pub async fn login_offline(
+    event_bus: Option<&EventBus>,
     username: String,
 ) -> Result<UserProfile, String> {
     let mut auth = OfflineAuth::new(username);
 
-    let profile = auth.authenticate(Some(&event_bus)).await.map_err(|e| {
+    let profile = auth.authenticate(event_bus).await.map_err(|e| {
         let msg = format!("Auth failed: {:?}", e);
         tracing::error!(%msg);
         msg
@@ -0,0 +1,356 @@
+use crate::commands::modpacks::{ensure_instance_idle, existing_instance_dir, validate_modpack_id};
+use crate::commands::settings::get_launcher_settings;
+use crate::types::SavesBackup;
+use crate::utils::sync;
+use lighty_launcher::core::AppState;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::{command, AppHandle};
+use zip::write::SimpleFileOptions;
+
+/// Dossier des sauvegardes, hors des instances pour survivre à une réinstallation
+const BACKUPS_DIR: &str = ".backups";
+
+/// Nombre de sauvegardes conservées par instance et par origine, par défaut
+pub const DEFAULT_BACKUP_RETENTION: usize = 5;
+
+const BACKUP_PREFIX: &str = "saves-";
+const BACKUP_EXTENSION: &str = "zip";
+
+/// Origine d'une sauvegarde, encodée dans le nom de l'archive
+#[derive(Debug, Clone, Copy)]
+pub enum BackupReason {
+    /// Demandée par le joueur
+    Manual,
+    /// Automatique, avant la mise à jour des fichiers du modpack
+    Update,
+    /// Automatique, état des saves remplacé par une restauration
+    Restore,
+}
+
+impl BackupReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupReason::Manual => "manual",
+            BackupReason::Update => "update",
+            BackupReason::Restore => "restore",
+        }
+    }
+}
+
+fn backups_dir(instance_id: &str) -> PathBuf {
+    AppState::get_project_dirs()
+        .data_dir()
+        .join(BACKUPS_DIR)
+        .join(instance_id)
+}
+
+/// L'instance a-t-elle des mondes à sauvegarder ?
+pub fn has_saves(instance_dir: &Path) -> bool {
+    std::fs::read_dir(instance_dir.join("saves"))
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+}
+
+/// Nombre de sauvegardes à conserver, d'après les settings du launcher
+pub fn backup_retention(app: &AppHandle) -> usize {
+    get_launcher_settings(app)
+        .backup_retention
+        .unwrap_or(DEFAULT_BACKUP_RETENTION)
+}
+
+/// Archive le dossier `saves/` d'une instance, puis applique la rétention (opération bloquante)
+pub fn create_backup(
+    instance_id: &str,
+    reason: BackupReason,
+    retention: usize,
+) -> Result<SavesBackup, String> {
+    let backup = create_archive(instance_id, reason)?;
+    prune_backups(instance_id, retention, None);
+    Ok(backup)
+}
+
+/// Archive le dossier `saves/` d'une instance, sans rétention
+fn create_archive(instance_id: &str, reason: BackupReason) -> Result<SavesBackup, String> {
+    let saves_dir = sync::instance_dir(instance_id).join("saves");
+    if !saves_dir.is_dir() {
+        return Err(format!("Modpack '{}' has no saves to back up", instance_id));
+    }
+
+    let dir = backups_dir(instance_id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut name = format!(
+        "{}{}-{}.{}",
+        BACKUP_PREFIX,
+        stamp,
+        reason.as_str(),
+        BACKUP_EXTENSION
+    );
+    let mut index = 1;
+    while dir.join(&name).exists() {
+        index += 1;
+        name = format!(
+            "{}{}-{}-{}.{}",
+            BACKUP_PREFIX,
+            stamp,
+            index,
+            reason.as_str(),
+            BACKUP_EXTENSION
+        );
+    }
+
+    let archive_path = dir.join(&name);
+    if let Err(e) = write_archive(&saves_dir, &archive_path) {
+        let _ = std::fs::remove_file(&archive_path);
+        return Err(e);
+    }
+    tracing::info!("Saves of '{}' backed up to {:?}", instance_id, archive_path);
+
+    Ok(backup_info(instance_id, &archive_path))
+}
+
+/// Sauvegardes d'une instance, de la plus récente à la plus ancienne
+pub fn list_backups(instance_id: &str) -> Vec<SavesBackup> {
+    let mut backups: Vec<SavesBackup> = std::fs::read_dir(backups_dir(instance_id))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| is_backup_archive(path))
+                .map(|path| backup_info(instance_id, &path))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Le nom commence par la date : l'ordre alphabétique est l'ordre chronologique
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+/// Supprime les sauvegardes les plus anciennes au-delà de `retention` (0 : aucune limite).
+///
+/// La limite s'applique par origine : des sauvegardes automatiques n'évincent jamais
+/// celles faites par le joueur. `keep` n'est jamais supprimée (archive en cours de restauration).
+pub fn prune_backups(instance_id: &str, retention: usize, keep: Option<&str>) {
+    if retention == 0 {
+        return;
+    }
+
+    let mut kept_by_reason: HashMap<String, usize> = HashMap::new();
+    for backup in list_backups(instance_id) {
+        let kept = kept_by_reason.entry(backup.reason.clone()).or_default();
+        if *kept < retention || keep == Some(backup.name.as_str()) {
+            *kept += 1;
+            continue;
+        }
+        let path = backups_dir(instance_id).join(&backup.name);
+        match std::fs::remove_file(&path) {
+            Ok(()) => tracing::info!("Removed old saves backup {:?}", path),
+            Err(e) => tracing::warn!("Failed to remove old backup {:?}: {}", path, e),
+        }
+    }
+}
+
+/// Applique la rétention aux sauvegardes de toutes les instances
+pub fn prune_all_backups(retention: usize) {
+    let root = AppState::get_project_dirs().data_dir().join(BACKUPS_DIR);
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if let Some(instance_id) = entry.file_name().to_str() {
+            prune_backups(instance_id, retention, None);
+        }
+    }
+}
+
+fn is_backup_archive(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    path.is_file()
+        && name.starts_with(BACKUP_PREFIX)
+        && path.extension().map(|ext| ext == BACKUP_EXTENSION) == Some(true)
+}
+
+fn backup_info(instance_id: &str, path: &Path) -> SavesBackup {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let metadata = path.metadata().ok();
+    let created_at = metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let reason = name
+        .trim_end_matches(&format!(".{}", BACKUP_EXTENSION))
+        .rsplit('-')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    SavesBackup {
+        name,
+        modpack_id: instance_id.to_string(),
+        reason,
+        size: metadata.map(|m| m.len()).unwrap_or(0),
+        created_at,
+    }
+}
+
+fn write_archive(saves_dir: &Path, archive_path: &Path) -> Result<(), String> {
+    let file = std::fs::File::create(archive_path)
+        .map_err(|e| format!("Failed to create '{}': {}", archive_path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    for entry in walkdir::WalkDir::new(saves_dir).min_depth(1) {
+        let entry = entry.map_err(|e| format!("Failed to read saves: {}", e))?;
+        let relative = entry
+            .path()
+            .strip_prefix(saves_dir)
+            .map_err(|e| e.to_string())?;
+        let name = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if entry.file_type().is_dir() {
+            zip.add_directory(name.as_str(), options)
+                .map_err(|e| format!("Failed to add '{}' to archive: {}", name, e))?;
+        } else if entry.file_type().is_file() {
+            let mut source = std::fs::File::open(entry.path())
+                .map_err(|e| format!("Failed to read '{}': {}", entry.path().display(), e))?;
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| format!("Failed to add '{}' to archive: {}", name, e))?;
+            std::io::copy(&mut source, &mut zip)
+                .map_err(|e| format!("Failed to write '{}' to archive: {}", name, e))?;
+        }
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize saves archive: {}", e))?;
+    Ok(())
+}
+
+fn extract_archive(archive_path: &Path, destination: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open '{}': {}", archive_path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Invalid backup archive '{}': {}", archive_path.display(), e))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read backup archive: {}", e))?;
+        // Refuse les chemins qui sortiraient du dossier de destination
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| format!("Unsafe path in backup archive: {}", entry.name()))?;
+        let target = destination.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        let mut output = std::fs::File::create(&target)
+            .map_err(|e| format!("Failed to create '{}': {}", target.display(), e))?;
+        std::io::copy(&mut entry, &mut output)
+            .map_err(|e| format!("Failed to extract '{}': {}", target.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Restaure une sauvegarde : l'archive est extraite à côté, les saves actuelles sont
+/// sauvegardées (raison `restore`) puis remplacées.
+fn restore_backup(instance_id: &str, backup_name: &str, retention: usize) -> Result<(), String> {
+    let archive_path = backups_dir(instance_id).join(backup_name);
+    let is_plain_name = !backup_name.contains(['/', '\\']) && backup_name != "..";
+    if !is_plain_name || !is_backup_archive(&archive_path) {
+        return Err(format!("Backup '{}' not found", backup_name));
+    }
+
+    let instance_dir = sync::instance_dir(instance_id);
+    let saves_dir = instance_dir.join("saves");
+    let staging_dir = instance_dir.join("saves.restoring");
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to clean '{}': {}", staging_dir.display(), e))?;
+    }
+    std::fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create '{}': {}", staging_dir.display(), e))?;
+
+    if let Err(e) = extract_archive(&archive_path, &staging_dir) {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+
+    // Rétention appliquée après la restauration, sans toucher à l'archive restaurée
+    if has_saves(&instance_dir) {
+        create_archive(instance_id, BackupReason::Restore)?;
+    }
+    if saves_dir.exists() {
+        std::fs::remove_dir_all(&saves_dir)
+            .map_err(|e| format!("Failed to replace '{}': {}", saves_dir.display(), e))?;
+    }
+    std::fs::rename(&staging_dir, &saves_dir)
+        .map_err(|e| format!("Failed to restore '{}': {}", saves_dir.display(), e))?;
+
+    tracing::info!("Restored saves of '{}' from {}", instance_id, backup_name);
+    prune_backups(instance_id, retention, Some(backup_name));
+    Ok(())
+}
+
+/// Commande Tauri : sauvegarde à la demande des saves d'une instance
+#[command]
+pub async fn create_saves_backup(
+    app: AppHandle,
+    modpack_id: String,
+) -> Result<SavesBackup, String> {
+    existing_instance_dir(&modpack_id)?;
+    ensure_instance_idle(&modpack_id)?;
+    let retention = backup_retention(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        create_backup(&modpack_id, BackupReason::Manual, retention)
+    })
+    .await
+    .map_err(|e| format!("Backup task failed: {}", e))?
+}
+
+/// Commande Tauri : liste les sauvegardes d'une instance (taille, date, raison)
+#[command]
+pub fn list_saves_backups(modpack_id: String) -> Result<Vec<SavesBackup>, String> {
+    validate_modpack_id(&modpack_id)?;
+    Ok(list_backups(&modpack_id))
+}
+
+/// Commande Tauri : restaure une sauvegarde des saves d'une instance
+#[command]
+pub async fn restore_saves_backup(
+    app: AppHandle,
+    modpack_id: String,
+    backup_name: String,
+) -> Result<(), String> {
+    existing_instance_dir(&modpack_id)?;
+    ensure_instance_idle(&modpack_id)?;
+    let retention = backup_retention(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        restore_backup(&modpack_id, &backup_name, retention)
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+}
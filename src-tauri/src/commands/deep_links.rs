@@ -0,0 +1,34 @@
+use crate::commands::accounts::display_active_account;
+use crate::commands::modpacks;
+use crate::types::DeepLinkPayload;
+use crate::utils::deep_link;
+use crate::utils::vault::VaultState;
+use tauri::State;
+
+/// Retourne (et retire) le lien `miratopia://` en attente de confirmation.
+///
+/// Appelée au montage du frontend (lien reçu au démarrage) et à chaque `lighty://deep-link`.
+#[tauri::command]
+pub fn take_pending_deep_link() -> Result<Option<DeepLinkPayload>, String> {
+    Ok(deep_link::take_pending())
+}
+
+/// Modpack à lancer pour un lien `miratopia://server/<host>` : le premier accessible au compte
+/// actif dont le `modpack.json` déclare ce serveur (`server`), `None` s'il n'y en a pas.
+///
+/// Le jeu est lancé sans argument de connexion directe : le joueur rejoint le serveur depuis
+/// la liste fournie par le pack.
+#[tauri::command]
+pub async fn find_server_modpack(
+    state: State<'_, VaultState>,
+    address: String,
+) -> Result<Option<String>, String> {
+    if !deep_link::is_server_address(&address) {
+        return Err(format!("Invalid server address '{}'", address));
+    }
+    let profile = display_active_account(state)
+        .await
+        .map_err(|e| format!("Failed to get active account: {}", e))?
+        .ok_or_else(|| "No active profile".to_string())?;
+    modpacks::find_modpack_by_server(&profile.username, &address).await
+}
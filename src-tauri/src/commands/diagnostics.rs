@@ -0,0 +1,270 @@
+use crate::commands::settings::SETTINGS_STORE;
+use crate::logging;
+use crate::utils::http;
+use crate::utils::redact;
+use lighty_launcher::core::AppState;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use sysinfo::{System, SystemExt};
+use tauri::{command, AppHandle, Manager};
+use tauri_plugin_store::StoreBuilder;
+use zip::write::SimpleFileOptions;
+
+/// Nombre maximum de crash reports inclus dans le bundle
+const MAX_CRASH_REPORTS: usize = 10;
+
+/// Nombre de lignes retournées par défaut par `get_launcher_logs`
+const DEFAULT_LOG_LINES: usize = 500;
+
+/// Informations système incluses dans le bundle de diagnostic
+#[derive(Debug, Serialize)]
+struct SystemInfo {
+    launcher_version: String,
+    tauri_version: String,
+    os: String,
+    arch: String,
+    family: String,
+    cpu_count: usize,
+    total_memory_mb: u64,
+    generated_at: String,
+}
+
+/// Retourne les dernières lignes du log du launcher (session courante)
+#[command]
+pub fn get_launcher_logs(max_lines: Option<usize>) -> Result<Vec<String>, String> {
+    let path = match logging::list_log_files().into_iter().next() {
+        Some(path) => path,
+        None => return Ok(Vec::new()),
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let max_lines = max_lines.unwrap_or(DEFAULT_LOG_LINES);
+    let start = lines.len().saturating_sub(max_lines);
+
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Génère une archive zip de diagnostic à envoyer au support.
+///
+/// L'archive contient les logs récents du launcher, le dernier log du jeu, les crash reports,
+/// les settings (secrets et chemins personnels masqués), les informations système et la version du launcher.
+/// Retourne le chemin de l'archive créée (dossier de téléchargements par défaut).
+#[command]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    destination: Option<String>,
+) -> Result<String, String> {
+    let destination_dir = match destination {
+        Some(dir) => PathBuf::from(dir),
+        None => app
+            .path()
+            .download_dir()
+            .map_err(|e| format!("Failed to resolve download dir: {}", e))?,
+    };
+    std::fs::create_dir_all(&destination_dir)
+        .map_err(|e| format!("Failed to create '{}': {}", destination_dir.display(), e))?;
+
+    let file_name = format!(
+        "miratopia-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let archive_path = destination_dir.join(file_name);
+
+    let settings = redacted_settings(&app)?;
+    let system = system_info(&app);
+
+    let path = archive_path.clone();
+    tauri::async_runtime::spawn_blocking(move || write_bundle(&path, &settings, &system))
+        .await
+        .map_err(|e| format!("Diagnostics task failed: {}", e))??;
+
+    tracing::info!("Diagnostics bundle exported to {:?}", archive_path);
+    Ok(archive_path.to_string_lossy().to_string())
+}
+
+fn write_bundle(
+    archive_path: &Path,
+    settings: &serde_json::Value,
+    system: &SystemInfo,
+) -> Result<(), String> {
+    let file = std::fs::File::create(archive_path)
+        .map_err(|e| format!("Failed to create '{}': {}", archive_path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    // Logs du launcher (sessions conservées par la rotation)
+    for path in logging::list_log_files() {
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            add_file(&mut zip, options, &format!("launcher/{}", name), &path)?;
+        }
+    }
+
+    // Dernier log du jeu et crash reports des instances
+    let instances = instance_dirs();
+    if let Some((instance, path)) = latest_game_log(&instances) {
+        add_file(
+            &mut zip,
+            options,
+            &format!("game/{}/latest.log", instance),
+            &path,
+        )?;
+    }
+    for (instance, path) in crash_reports(&instances) {
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            add_file(
+                &mut zip,
+                options,
+                &format!("game/{}/crash-reports/{}", instance, name),
+                &path,
+            )?;
+        }
+    }
+
+    let settings = serde_json::to_vec_pretty(settings).map_err(|e| e.to_string())?;
+    add_bytes(&mut zip, options, "settings.json", &settings)?;
+
+    let system = serde_json::to_vec_pretty(system).map_err(|e| e.to_string())?;
+    add_bytes(&mut zip, options, "system.json", &system)?;
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize diagnostics archive: {}", e))?;
+    Ok(())
+}
+
+fn add_file(
+    zip: &mut zip::ZipWriter<std::fs::File>,
+    options: SimpleFileOptions,
+    name: &str,
+    path: &Path,
+) -> Result<(), String> {
+    match std::fs::read(path) {
+        Ok(content) => add_bytes(zip, options, name, &redact::redact_bytes(&content)),
+        Err(e) => {
+            // Un fichier verrouillé (log en cours d'écriture) ne doit pas bloquer l'export
+            tracing::warn!("Skipping '{}' in diagnostics bundle: {}", path.display(), e);
+            Ok(())
+        }
+    }
+}
+
+fn add_bytes(
+    zip: &mut zip::ZipWriter<std::fs::File>,
+    options: SimpleFileOptions,
+    name: &str,
+    content: &[u8],
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to add '{}' to archive: {}", name, e))?;
+    zip.write_all(content)
+        .map_err(|e| format!("Failed to write '{}' to archive: {}", name, e))
+}
+
+/// Dossiers des instances présentes dans le dossier de données
+fn instance_dirs() -> Vec<(String, PathBuf)> {
+    let launcher_dir = AppState::get_project_dirs();
+    std::fs::read_dir(launcher_dir.data_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_str()?.to_string();
+                    Some((name, path))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn modified_at(path: &Path) -> SystemTime {
+    path.metadata()
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// `logs/latest.log` le plus récent parmi toutes les instances
+fn latest_game_log(instances: &[(String, PathBuf)]) -> Option<(String, PathBuf)> {
+    instances
+        .iter()
+        .map(|(name, dir)| (name.clone(), dir.join("logs").join("latest.log")))
+        .filter(|(_, path)| path.is_file())
+        .max_by_key(|(_, path)| modified_at(path))
+}
+
+/// Crash reports Minecraft et crash logs de la JVM (`hs_err_pid*.log`), du plus récent au plus ancien
+fn crash_reports(instances: &[(String, PathBuf)]) -> Vec<(String, PathBuf)> {
+    let mut reports = Vec::new();
+
+    for (name, dir) in instances {
+        let crash_dir = dir.join("crash-reports");
+        for source in [crash_dir.as_path(), dir.as_path()] {
+            let entries = match std::fs::read_dir(source) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+                let file_name = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default();
+                let is_report = if source == crash_dir {
+                    file_name.ends_with(".txt")
+                } else {
+                    file_name.starts_with("hs_err_pid") && file_name.ends_with(".log")
+                };
+                if is_report && path.is_file() {
+                    reports.push((name.clone(), path));
+                }
+            }
+        }
+    }
+
+    reports.sort_by_key(|(_, path)| std::cmp::Reverse(modified_at(path)));
+    reports.truncate(MAX_CRASH_REPORTS);
+    reports
+}
+
+/// Contenu du store de settings, passé par le masquage des secrets et chemins personnels
+fn redacted_settings(app: &AppHandle) -> Result<serde_json::Value, String> {
+    let store = StoreBuilder::new(app, std::path::Path::new(SETTINGS_STORE))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut settings: serde_json::Map<String, serde_json::Value> =
+        store.entries().into_iter().collect();
+    // Identifiants du proxy retirés explicitement : `redact` ignore les secrets courts
+    if let Some(proxy_url) = settings
+        .get_mut("launcher")
+        .and_then(|launcher| launcher.get_mut("proxyUrl"))
+    {
+        if let Some(url) = proxy_url.as_str().map(http::strip_credentials) {
+            *proxy_url = serde_json::Value::String(url);
+        }
+    }
+    let raw = serde_json::to_vec(&settings).map_err(|e| e.to_string())?;
+
+    serde_json::from_slice(&redact::redact_bytes(&raw)).map_err(|e| e.to_string())
+}
+
+fn system_info(app: &AppHandle) -> SystemInfo {
+    let mut sys = System::new();
+    sys.refresh_system();
+
+    SystemInfo {
+        launcher_version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        family: std::env::consts::FAMILY.to_string(),
+        cpu_count: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(0),
+        total_memory_mb: sys.get_total_memory() / 1024,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
@@ -1,7 +1,11 @@
 use tauri::ipc::Invoke;
 
 pub mod accounts;
+pub mod backups;
+pub mod deep_links;
+pub mod diagnostics;
 pub mod modpacks;
+pub mod sessions;
 pub mod settings;
 pub mod utils;
 pub mod window;
@@ -9,9 +13,23 @@ pub mod window;
 pub fn handler() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
     tauri::generate_handler![
         window::open_console_window,
+        window::get_console_history,
         modpacks::list_modpacks,
         modpacks::start_modpack,
         modpacks::stop_modpack,
+        modpacks::get_modpack_disk_usage,
+        modpacks::delete_modpack,
+        modpacks::install_modpack,
+        modpacks::reinstall_modpack,
+        modpacks::check_modpack_updates,
+        modpacks::take_pending_modpack_updates,
+        modpacks::validate_modpack_manifest,
+        backups::create_saves_backup,
+        backups::list_saves_backups,
+        backups::restore_saves_backup,
+        modpacks::cancel_launch,
+        modpacks::pause_downloads,
+        modpacks::resume_downloads,
         modpacks::open_modpacks_folder,
         modpacks::delete_all_modpacks,
         accounts::display_account,
@@ -25,8 +43,20 @@ pub fn handler() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
         settings::display_modpack_settings,
         settings::update_modpack_settings,
         settings::reset_all_settings,
+        settings::display_launcher_settings,
+        settings::update_launcher_settings,
+        settings::set_log_level,
+        settings::set_backup_retention,
+        settings::is_restart_required,
         utils::os_total_memory_info,
         utils::open_launcher_folder,
         utils::clear_cache,
+        utils::restart_launcher,
+        diagnostics::get_launcher_logs,
+        diagnostics::export_diagnostics,
+        deep_links::take_pending_deep_link,
+        deep_links::find_server_modpack,
+        sessions::get_play_stats,
+        sessions::get_recent_sessions,
     ]
 }
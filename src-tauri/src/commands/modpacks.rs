@@ -1,20 +1,45 @@
 use crate::commands::accounts::{display_account, display_active_account, get_active_account};
-use crate::commands::settings::get_modpack_settings;
+use crate::commands::backups::{self, BackupReason};
+use crate::commands::settings::{get_modpack_settings, Settings};
+use crate::events;
+use crate::runners::tray::{self, TrayStatus};
+use crate::types::{DiskUsage, FileChange, ManifestReport, ModpackUpdatePayload, VersionChange};
+use crate::utils::deep_link;
+use crate::utils::download::{self, DownloadControl, LaunchGuard};
+use crate::utils::manifest;
+use crate::utils::mirrors;
+use crate::utils::paths;
+use crate::utils::redact;
+use crate::utils::running;
+use crate::utils::sessions;
+use crate::utils::signature;
+use crate::utils::store;
+use crate::utils::sync::{self, SyncEvent, SyncFile};
 use crate::utils::vault::VaultState;
 use lighty_launcher::prelude::InstanceControl;
+use lighty_launcher::prelude::*;
 use lighty_launcher::Loader;
-use lighty_launcher::{loaders::Mods, prelude::*};
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{Emitter, State};
+use tokio::sync::mpsc;
+
+/// Configuration globale du launcher (liste des modpacks, miroirs)
+const LAUNCHER_JSON_URL: &str =
+    "https://raw.githubusercontent.com/tacxtv/miratopia-launcher/refs/heads/config/launcher.json";
+
+/// Manifest du dernier install réussi, conservé dans le dossier de l'instance
+const INSTALLED_MANIFEST_FILE: &str = ".installed-modpack.json";
 
 static MC_INSTANCE: Lazy<Mutex<Option<VersionBuilder<'static, Loader>>>> =
     Lazy::new(|| Mutex::new(None));
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct MinecraftModpackInfo {
     version: String,
     #[serde(rename = "recommendedMemory")]
@@ -22,25 +47,65 @@ struct MinecraftModpackInfo {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct LoaderModpackInfo {
     #[serde(rename = "type")]
     loader_type: String,
+    /// Vide pour une entrée `vanilla`
+    #[serde(default)]
     version: String,
+    /// Entrée à utiliser quand plusieurs loaders sont listés
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    default: bool,
+}
+
+impl TryFrom<&LoaderModpackInfo> for Loader {
+    type Error = String;
+
+    fn try_from(info: &LoaderModpackInfo) -> Result<Self, Self::Error> {
+        let loader = match info.loader_type.trim().to_ascii_lowercase().as_str() {
+            "vanilla" => return Ok(Loader::Vanilla),
+            "fabric" => Loader::Fabric,
+            "forge" => Loader::Forge,
+            "neoforge" => Loader::NeoForge,
+            "quilt" => Loader::Quilt,
+            other => {
+                return Err(format!(
+                "Unsupported loader type '{}' (expected vanilla, fabric, forge, neoforge or quilt)",
+                other
+            ))
+            }
+        };
+
+        if info.version.trim().is_empty() {
+            return Err(format!(
+                "Loader '{}' has no version in modpack.json",
+                info.loader_type
+            ));
+        }
+        Ok(loader)
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct FileModpackInfo {
     url: String,
     path: String,
     hash: String,
     size: u64,
+    /// URLs de secours, essayées après `url`
+    #[serde(default)]
+    mirrors: Vec<String>,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct ModpackInfo {
+    /// Version du schéma (`manifest::SUPPORTED_SCHEMA_VERSIONS`), 1 si absente
+    #[serde(rename = "schemaVersion", default)]
+    schema_version: Option<u64>,
+
     id: String,
     name: String,
     #[serde(rename = "default")]
@@ -62,6 +127,24 @@ struct ModpackInfo {
 
     #[serde(rename = "ignoredFiles", default)]
     ignored_files: Option<Vec<String>>,
+
+    /// Notes de version affichées lors d'une mise à jour
+    #[serde(default)]
+    changelog: Option<String>,
+
+    /// Serveur du pack (`host[:port]`), cible des liens `miratopia://server/<host>`
+    #[serde(default)]
+    server: Option<String>,
+}
+
+/// Contenu de `INSTALLED_MANIFEST_FILE`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledManifest {
+    /// Nom du dossier du manifest distant (`modpacks/<name>/modpack.json`)
+    modpack_name: String,
+    installed_at: String,
+    manifest: ModpackInfo,
 }
 
 #[tauri::command]
@@ -99,7 +182,10 @@ pub fn open_modpacks_folder() -> Result<(), String> {
     Ok(())
 }
 
-/// Remove all modpacks from the data directory
+/// Remove all modpacks from the data directory.
+///
+/// Les dossiers partagés (`.store`, `.backups`...) sont conservés : les sauvegardes des
+/// mondes survivent à la suppression des instances.
 #[tauri::command]
 pub fn delete_all_modpacks() -> Result<(), String> {
     let launcher_dir = AppState::get_project_dirs();
@@ -113,20 +199,225 @@ pub fn delete_all_modpacks() -> Result<(), String> {
     let entries = std::fs::read_dir(&data_path)
         .map_err(|e| format!("Failed to read data directory: {}", e))?;
 
+    let mut instances = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
-        if path.is_dir() {
-            std::fs::remove_dir_all(&path)
-                .map_err(|e| format!("Failed to delete '{}': {}", path.display(), e))?;
-            tracing::info!("Deleted modpack directory: {:?}", path);
+        let Some(modpack_id) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if path.is_dir() && validate_modpack_id(&modpack_id).is_ok() {
+            instances.push((modpack_id, path));
         }
     }
 
+    // Rien n'est supprimé si une instance est en cours d'utilisation
+    for (modpack_id, _) in &instances {
+        ensure_instance_idle(modpack_id)?;
+    }
+
+    for (modpack_id, path) in instances {
+        std::fs::remove_dir_all(&path)
+            .map_err(|e| format!("Failed to delete '{}': {}", path.display(), e))?;
+        store::release_instance(&modpack_id);
+        tracing::info!("Deleted modpack directory: {:?}", path);
+    }
+
     tracing::info!("All modpacks deleted from {:?}", data_path);
     Ok(())
 }
 
+/// Refuse les identifiants qui sortiraient du dossier des instances
+/// ou viseraient un dossier partagé (`.store`, `.backups`...)
+pub fn validate_modpack_id(modpack_id: &str) -> Result<(), String> {
+    let is_plain_name = !modpack_id.is_empty()
+        && !modpack_id.starts_with('.')
+        && !modpack_id.contains(['/', '\\', ':']);
+    if !is_plain_name {
+        return Err(format!("Invalid modpack id '{}'", modpack_id));
+    }
+    Ok(())
+}
+
+/// Dossier d'une instance existante
+pub fn existing_instance_dir(modpack_id: &str) -> Result<PathBuf, String> {
+    validate_modpack_id(modpack_id)?;
+
+    let instance_dir = sync::instance_dir(modpack_id);
+    if !instance_dir.is_dir() {
+        return Err(format!("Modpack '{}' is not installed", modpack_id));
+    }
+    Ok(instance_dir)
+}
+
+/// Refuse d'agir sur une instance en cours de lancement (synchronisation comprise) ou dont
+/// le jeu tourne
+pub fn ensure_instance_idle(modpack_id: &str) -> Result<(), String> {
+    if running::is_running(modpack_id) {
+        return Err(format!("Modpack '{}' is running", modpack_id));
+    }
+    if download::is_instance_launching(modpack_id) {
+        return Err(format!("Modpack '{}' is being launched", modpack_id));
+    }
+    Ok(())
+}
+
+/// Supprime le contenu d'une instance, en gardant éventuellement `saves/`
+fn remove_instance_files(instance_dir: &Path, keep_saves: bool) -> Result<(), String> {
+    if !keep_saves {
+        return std::fs::remove_dir_all(instance_dir)
+            .map_err(|e| format!("Failed to delete '{}': {}", instance_dir.display(), e));
+    }
+
+    let entries = std::fs::read_dir(instance_dir)
+        .map_err(|e| format!("Failed to read '{}': {}", instance_dir.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        if entry.file_name() == "saves" {
+            continue;
+        }
+        let path = entry.path();
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        result.map_err(|e| format!("Failed to delete '{}': {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Catégorie d'un dossier de premier niveau de l'instance
+fn disk_usage_category<'a>(usage: &'a mut DiskUsage, name: &str) -> &'a mut u64 {
+    match name {
+        "mods" => &mut usage.mods,
+        "config" | "defaultconfigs" => &mut usage.config,
+        "saves" => &mut usage.saves,
+        "logs" | "crash-reports" => &mut usage.logs,
+        "libraries" | "natives" => &mut usage.libraries,
+        "assets" => &mut usage.assets,
+        "runtime" | "jre" | "java" => &mut usage.runtime,
+        _ => &mut usage.other,
+    }
+}
+
+fn compute_disk_usage(instance_dir: &Path) -> DiskUsage {
+    let mut usage = DiskUsage::default();
+    for entry in walkdir::WalkDir::new(instance_dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+    {
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        let top_level = entry
+            .path()
+            .strip_prefix(instance_dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Un fichier à la racine de l'instance (options.txt...) n'a pas de sous-dossier
+        let category = if entry.depth() == 1 {
+            ""
+        } else {
+            top_level.as_str()
+        };
+
+        *disk_usage_category(&mut usage, category) += size;
+        usage.total += size;
+    }
+    usage
+}
+
+/// Taille d'une instance, par catégorie (mods, config, saves, logs, libraries, assets, runtime)
+#[tauri::command]
+pub async fn get_modpack_disk_usage(modpack_id: String) -> Result<DiskUsage, String> {
+    let instance_dir = existing_instance_dir(&modpack_id)?;
+    tauri::async_runtime::spawn_blocking(move || compute_disk_usage(&instance_dir))
+        .await
+        .map_err(|e| format!("Failed to compute disk usage: {}", e))
+}
+
+/// Supprime une instance (ses saves peuvent être conservées).
+///
+/// Seul le dossier de l'instance est touché : les runtimes Java, les assets partagés et
+/// le store restent en place (les objets du store qui ne servent plus sont libérés).
+#[tauri::command]
+pub fn delete_modpack(modpack_id: String, keep_saves: bool) -> Result<(), String> {
+    let instance_dir = existing_instance_dir(&modpack_id)?;
+    ensure_instance_idle(&modpack_id)?;
+
+    remove_instance_files(&instance_dir, keep_saves)?;
+    store::release_instance(&modpack_id);
+
+    tracing::info!(
+        "Deleted modpack '{}' (saves kept: {})",
+        modpack_id,
+        keep_saves
+    );
+    Ok(())
+}
+
+/// Réinstalle les fichiers du modpack : l'instance est vidée (saves conservées) puis
+/// les fichiers du manifest sont retéléchargés. Le loader, les librairies et les assets
+/// sont réinstallés par lighty au prochain lancement.
+///
+/// L'identifiant doit être le nom du dossier du manifest (`modpacks/<id>/modpack.json`).
+/// La progression, l'annulation et la pause passent par les mêmes commandes qu'un lancement.
+#[tauri::command]
+pub async fn reinstall_modpack(
+    app_handle: tauri::AppHandle,
+    state: State<'_, VaultState>,
+    modpack_id: String,
+) -> Result<(), String> {
+    let instance_dir = existing_instance_dir(&modpack_id)?;
+    ensure_instance_idle(&modpack_id)?;
+    let profile = display_active_account(state)
+        .await
+        .map_err(|e| format!("Failed to get active account: {}", e))?
+        .ok_or_else(|| "No active profile".to_string())?;
+    ensure_modpack_allowed(&profile.username, &modpack_id).await?;
+    let launch = LaunchGuard::new(&modpack_id)?;
+
+    let modpack = fetch_modpack(&modpack_id).await?;
+    if modpack.id != modpack_id {
+        return Err(format!(
+            "Manifest '{}' describes modpack '{}'",
+            modpack_id, modpack.id
+        ));
+    }
+
+    // Références du store conservées : `set_instance_refs` les remplace en fin de synchronisation,
+    // les objets déjà téléchargés ne sont pas supprimés puis retéléchargés
+    remove_instance_files(&instance_dir, true)?;
+    tracing::info!("Reinstalling modpack '{}'", modpack_id);
+
+    let sync_events = events::spawn_sync_listener(app_handle.clone(), modpack_id.clone());
+    let retention = backups::backup_retention(&app_handle);
+    if let Err(e) = sync_modpack_files(
+        &modpack,
+        &modpack_id,
+        launch.control(),
+        retention,
+        sync_events,
+    )
+    .await
+    {
+        if launch.control().is_cancelled() {
+            return Err(cancel_launch_cleanup(&app_handle, &instance_dir, &modpack_id).await);
+        }
+        let msg = format!("Failed to reinstall modpack files: {}", e);
+        tracing::error!(%msg);
+        events::reset_launch_progress(&app_handle).await;
+        tray::set_status(&app_handle, TrayStatus::Error(msg.clone()));
+        return Err(msg);
+    }
+
+    tray::settle_status(&app_handle);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn list_modpacks(state: State<'_, VaultState>) -> Result<Vec<String>, String> {
     let profile_name = display_active_account(state.clone())
@@ -135,14 +426,21 @@ pub async fn list_modpacks(state: State<'_, VaultState>) -> Result<Vec<String>,
         .ok_or_else(|| "No active profile".to_string())?
         .username;
 
-    // Télécharger le JSON principal
-    let url = "https://raw.githubusercontent.com/tacxtv/miratopia-launcher/refs/heads/config/launcher.json";
-    let json: Value = reqwest::get(url)
-        .await
-        .map_err(|e| format!("Failed to download launcher.json: {}", e))?
-        .json()
+    // Récupérer le compte
+    let profile = display_account(state, &profile_name)
         .await
-        .map_err(|e| format!("Failed to parse launcher.json: {}", e))?;
+        .map_err(|e| format!("Failed to get account: {}", e))?
+        .ok_or_else(|| "Profile not found".to_string())?;
+
+    allowed_modpacks(&profile.username).await
+}
+
+/// Modpacks de `launcher.json` accessibles au joueur (whitelist)
+pub async fn allowed_modpacks(username: &str) -> Result<Vec<String>, String> {
+    let username = username.to_lowercase();
+
+    // Télécharger le JSON principal
+    let json = fetch_launcher_json().await?;
 
     // Extraire le tableau des modpacks
     let config = json.get("config").ok_or("No config found")?;
@@ -151,13 +449,6 @@ pub async fn list_modpacks(state: State<'_, VaultState>) -> Result<Vec<String>,
         .and_then(|v| v.as_array())
         .ok_or("No modpacks array found")?;
 
-    // Récupérer le compte
-    let profile = display_account(state, &profile_name)
-        .await
-        .map_err(|e| format!("Failed to get account: {}", e))?
-        .ok_or_else(|| "Profile not found".to_string())?;
-    let username = profile.username.to_lowercase();
-
     // Filtrer les modpacks accessibles
     let mut allowed = Vec::new();
     for modpack in modpacks {
@@ -183,6 +474,535 @@ pub async fn list_modpacks(state: State<'_, VaultState>) -> Result<Vec<String>,
     Ok(allowed)
 }
 
+/// Refuse un modpack hors de la whitelist du joueur : le frontend ne suffit pas, un lien
+/// `miratopia://` ou un appel direct peut viser n'importe quel nom
+pub async fn ensure_modpack_allowed(username: &str, modpack_name: &str) -> Result<(), String> {
+    if allowed_modpacks(username)
+        .await?
+        .iter()
+        .any(|name| name == modpack_name)
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Modpack '{}' is not available for {}",
+            modpack_name, username
+        ))
+    }
+}
+
+/// Modpack accessible au joueur dont le manifest déclare le serveur `address`
+pub async fn find_modpack_by_server(
+    username: &str,
+    address: &str,
+) -> Result<Option<String>, String> {
+    for modpack_name in allowed_modpacks(username).await? {
+        let modpack = match fetch_modpack(&modpack_name).await {
+            Ok(modpack) => modpack,
+            Err(e) => {
+                tracing::warn!("Skipping '{}' in server lookup: {}", modpack_name, e);
+                continue;
+            }
+        };
+        let declares_server = modpack
+            .server
+            .as_deref()
+            .is_some_and(|server| deep_link::same_server(server, address));
+        if declares_server {
+            return Ok(Some(modpack_name));
+        }
+    }
+    Ok(None)
+}
+
+async fn fetch_launcher_json() -> Result<Value, String> {
+    let content = signature::fetch_signed(LAUNCHER_JSON_URL).await?;
+    serde_json::from_slice(&content).map_err(|e| format!("Failed to parse launcher.json: {}", e))
+}
+
+/// URLs de base des miroirs globaux (`config.mirrors` de `launcher.json`).
+///
+/// Les miroirs sont facultatifs : en cas d'erreur, on continue avec les URLs du manifest.
+async fn fetch_global_mirrors() -> Vec<String> {
+    match fetch_launcher_json().await {
+        Ok(json) => json
+            .get("config")
+            .and_then(|config| config.get("mirrors"))
+            .and_then(|mirrors| mirrors.as_array())
+            .map(|mirrors| {
+                mirrors
+                    .iter()
+                    .filter_map(|mirror| mirror.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Global mirrors unavailable: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Télécharge et parse le manifest `modpack.json` d'un modpack
+async fn fetch_modpack(modpack_name: &str) -> Result<ModpackInfo, String> {
+    let modpack_url = format!(
+        "https://raw.githubusercontent.com/tacxtv/miratopia-launcher/refs/heads/config/modpacks/{}/modpack.json",
+        modpack_name,
+    );
+    // Signature vérifiée avant tout parsing : un manifest modifié n'est jamais utilisé
+    let content = signature::fetch_signed(&modpack_url).await?;
+    let modpack = serde_json::from_slice::<ModpackInfo>(&content).map_err(|e| {
+        let msg = format!("Failed to parse modpack JSON from {}: {}", modpack_url, e);
+        tracing::error!(%msg);
+        msg
+    })?;
+
+    let schema_version = modpack
+        .schema_version
+        .unwrap_or(manifest::CURRENT_SCHEMA_VERSION);
+    if !manifest::SUPPORTED_SCHEMA_VERSIONS.contains(&schema_version) {
+        return Err(format!(
+            "Modpack '{}' uses manifest schema v{}, please update the launcher",
+            modpack_name, schema_version
+        ));
+    }
+
+    // L'id, les chemins et les hashes (objets du store) deviennent des chemins sur le disque :
+    // on refuse le manifest entier plutôt que d'écrire hors de l'instance
+    validate_modpack_id(&modpack.id)
+        .map_err(|e| format!("Modpack '{}' rejected: {}", modpack_name, e))?;
+    for (index, file) in modpack.files_info.iter().enumerate() {
+        if !store::is_sha1(&file.hash) {
+            let msg = format!(
+                "Modpack '{}' rejected: files[{}] ({}): hash '{}' is not a 40-character hex SHA-1",
+                modpack_name, index, file.url, file.hash
+            );
+            tracing::error!(%msg);
+            return Err(msg);
+        }
+        paths::check_relative_path(&file.path).map_err(|e| {
+            let msg = format!(
+                "Modpack '{}' rejected: files[{}] ({}): {}",
+                modpack_name, index, file.url, e
+            );
+            tracing::error!(%msg);
+            msg
+        })?;
+    }
+    Ok(modpack)
+}
+
+/// Valide un `modpack.json` (fichier local, URL ou nom de modpack publié).
+///
+/// `check_urls` envoie une requête `HEAD` par fichier pour comparer les tailles.
+#[tauri::command]
+pub async fn validate_modpack_manifest(
+    source: String,
+    check_urls: Option<bool>,
+) -> Result<ManifestReport, String> {
+    let value = manifest::load_manifest(&source).await?;
+    if check_urls.unwrap_or(false) {
+        Ok(manifest::validate_manifest_with_urls(&value).await)
+    } else {
+        Ok(manifest::validate_manifest(&value))
+    }
+}
+
+/// Synchronise les fichiers du manifest dans l'instance (reprise, pause et annulation).
+///
+/// Si des fichiers doivent changer et que l'instance a des mondes, les saves sont
+/// sauvegardées avant : une mise à jour qui casse un monde reste réversible.
+async fn sync_modpack_files(
+    modpack: &ModpackInfo,
+    modpack_name: &str,
+    control: &DownloadControl,
+    backup_retention: usize,
+    sync_events: mpsc::UnboundedSender<SyncEvent>,
+) -> Result<(), String> {
+    // `delete_modpack` / `reinstall_modpack` vérifient l'instance, pas le nom du modpack
+    download::bind_launch_instance(modpack_name, &modpack.id);
+    let instance_dir = sync::instance_dir(&modpack.id);
+    let global_mirrors = fetch_global_mirrors().await;
+    let files = modpack
+        .files_info
+        .iter()
+        .map(|file| SyncFile {
+            urls: mirrors::candidate_urls(&file.url, &file.mirrors, &global_mirrors),
+            path: file.path.clone(),
+            sha1: file.hash.clone(),
+            size: file.size,
+        })
+        .collect();
+    let ignored = modpack.ignored_files.clone().unwrap_or_default();
+    // Fichiers gérés par la version installée : ceux retirés du manifest sont supprimés
+    let previous: Vec<String> = read_installed_manifest(&instance_dir)
+        .map(|installed| {
+            installed
+                .manifest
+                .files_info
+                .into_iter()
+                .map(|file| file.path)
+                .collect()
+        })
+        .unwrap_or_default();
+    let plan = sync::plan_sync(&instance_dir, files, &ignored, &previous).await?;
+
+    if !plan.is_empty() && backups::has_saves(&instance_dir) {
+        let modpack_id = modpack.id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            backups::create_backup(&modpack_id, BackupReason::Update, backup_retention)
+        })
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))?
+        .map_err(|e| format!("Failed to back up saves before update: {}", e))?;
+    }
+
+    sync::apply_sync(plan, control, sync_events).await?;
+
+    // Référence pour `check_modpack_updates` : une erreur ici ne doit pas bloquer le lancement
+    if let Err(e) = write_installed_manifest(&instance_dir, modpack_name, modpack) {
+        tracing::warn!(
+            "Failed to store installed manifest of '{}': {}",
+            modpack.id,
+            e
+        );
+    }
+    Ok(())
+}
+
+fn read_installed_manifest(instance_dir: &Path) -> Option<InstalledManifest> {
+    let content = std::fs::read_to_string(instance_dir.join(INSTALLED_MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| tracing::warn!("Invalid installed manifest in {:?}: {}", instance_dir, e))
+        .ok()
+}
+
+fn write_installed_manifest(
+    instance_dir: &Path,
+    modpack_name: &str,
+    modpack: &ModpackInfo,
+) -> Result<(), String> {
+    let installed = InstalledManifest {
+        modpack_name: modpack_name.to_string(),
+        installed_at: chrono::Local::now().to_rfc3339(),
+        manifest: modpack.clone(),
+    };
+    let content = serde_json::to_string_pretty(&installed).map_err(|e| e.to_string())?;
+    std::fs::write(instance_dir.join(INSTALLED_MANIFEST_FILE), content).map_err(|e| e.to_string())
+}
+
+/// Entrée de loader retenue pour un manifest, `None` pour un pack vanilla.
+///
+/// Règle de sélection : sans entrée, le pack est vanilla ; avec plusieurs entrées,
+/// celle marquée `"default": true` est utilisée, sinon la première de la liste.
+fn select_loader(modpack: &ModpackInfo) -> Result<Option<&LoaderModpackInfo>, String> {
+    let defaults: Vec<&LoaderModpackInfo> = modpack
+        .modloader_info
+        .iter()
+        .filter(|loader| loader.default)
+        .collect();
+    if defaults.len() > 1 {
+        return Err(format!(
+            "Modpack '{}' marks {} loaders as default, expected at most one",
+            modpack.id,
+            defaults.len()
+        ));
+    }
+
+    let selected = defaults
+        .into_iter()
+        .next()
+        .or_else(|| modpack.modloader_info.first());
+    Ok(selected.filter(|loader| !loader.loader_type.eq_ignore_ascii_case("vanilla")))
+}
+
+/// Loader utilisé par un manifest (`fabric 0.16.9`), `None` pour un pack vanilla
+fn loader_label(modpack: &ModpackInfo) -> Option<String> {
+    select_loader(modpack)
+        .ok()
+        .flatten()
+        .map(|loader| format!("{} {}", loader.loader_type, loader.version))
+}
+
+/// Compare le manifest installé au manifest distant
+fn diff_manifests(
+    modpack_name: &str,
+    installed: &ModpackInfo,
+    remote: &ModpackInfo,
+) -> ModpackUpdatePayload {
+    let installed_files: HashMap<&str, &str> = installed
+        .files_info
+        .iter()
+        .map(|file| (file.path.as_str(), file.hash.as_str()))
+        .collect();
+    let remote_files: HashMap<&str, &str> = remote
+        .files_info
+        .iter()
+        .map(|file| (file.path.as_str(), file.hash.as_str()))
+        .collect();
+    let is_mod = |path: &str| path.starts_with("mods/");
+
+    let mut mods_added = Vec::new();
+    let mut mods_removed = Vec::new();
+    let mut mods_updated = Vec::new();
+    let mut other_files_changed = 0;
+
+    for (path, new_hash) in &remote_files {
+        let change = match installed_files.get(path) {
+            None => FileChange {
+                path: path.to_string(),
+                old_hash: None,
+                new_hash: Some(new_hash.to_string()),
+            },
+            Some(old_hash) if !old_hash.eq_ignore_ascii_case(new_hash) => FileChange {
+                path: path.to_string(),
+                old_hash: Some(old_hash.to_string()),
+                new_hash: Some(new_hash.to_string()),
+            },
+            Some(_) => continue,
+        };
+        if !is_mod(path) {
+            other_files_changed += 1;
+        } else if change.old_hash.is_none() {
+            mods_added.push(change);
+        } else {
+            mods_updated.push(change);
+        }
+    }
+    for (path, old_hash) in &installed_files {
+        if remote_files.contains_key(path) {
+            continue;
+        }
+        if is_mod(path) {
+            mods_removed.push(FileChange {
+                path: path.to_string(),
+                old_hash: Some(old_hash.to_string()),
+                new_hash: None,
+            });
+        } else {
+            other_files_changed += 1;
+        }
+    }
+    for changes in [&mut mods_added, &mut mods_removed, &mut mods_updated] {
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    let (old_loader, new_loader) = (loader_label(installed), loader_label(remote));
+    let loader_change = (old_loader != new_loader).then_some(VersionChange {
+        from: old_loader,
+        to: new_loader,
+    });
+    let minecraft_change = (installed.minecraft_info.version != remote.minecraft_info.version)
+        .then(|| VersionChange {
+            from: Some(installed.minecraft_info.version.clone()),
+            to: Some(remote.minecraft_info.version.clone()),
+        });
+
+    let has_update = !mods_added.is_empty()
+        || !mods_removed.is_empty()
+        || !mods_updated.is_empty()
+        || other_files_changed > 0
+        || loader_change.is_some()
+        || minecraft_change.is_some();
+
+    ModpackUpdatePayload {
+        modpack_name: modpack_name.to_string(),
+        modpack_id: remote.id.clone(),
+        installed: true,
+        has_update,
+        mods_added,
+        mods_removed,
+        mods_updated,
+        other_files_changed,
+        loader_change,
+        minecraft_change,
+        changelog: remote.changelog.clone(),
+    }
+}
+
+/// Compare le manifest installé d'un modpack au manifest distant.
+///
+/// Sans manifest installé (jamais lancé, ou installé par une ancienne version du launcher),
+/// `installed` vaut `false` et aucune différence n'est listée.
+#[tauri::command]
+pub async fn check_modpack_updates(modpack_name: String) -> Result<ModpackUpdatePayload, String> {
+    let remote = fetch_modpack(&modpack_name).await?;
+    let instance_dir = sync::instance_dir(&remote.id);
+
+    match read_installed_manifest(&instance_dir) {
+        Some(installed) => Ok(diff_manifests(&modpack_name, &installed.manifest, &remote)),
+        None => Ok(ModpackUpdatePayload {
+            modpack_name,
+            modpack_id: remote.id.clone(),
+            installed: false,
+            has_update: false,
+            mods_added: Vec::new(),
+            mods_removed: Vec::new(),
+            mods_updated: Vec::new(),
+            other_files_changed: 0,
+            loader_change: None,
+            minecraft_change: None,
+            changelog: remote.changelog.clone(),
+        }),
+    }
+}
+
+/// Instances installées par le launcher (avec leur manifest installé)
+fn installed_modpacks() -> Vec<(PathBuf, InstalledManifest)> {
+    let data_dir = AppState::get_project_dirs().data_dir().to_path_buf();
+    let Ok(entries) = std::fs::read_dir(&data_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            read_installed_manifest(&path).map(|installed| (path, installed))
+        })
+        .collect()
+}
+
+/// Noms (`modpacks/<name>/modpack.json`) des modpacks installés
+pub fn installed_modpack_names() -> Vec<String> {
+    installed_modpacks()
+        .into_iter()
+        .map(|(_, installed)| installed.modpack_name)
+        .collect()
+}
+
+/// Modpacks installés : identifiant d'instance (`manifest.id`, clé des sessions et des jeux
+/// en cours) et nom dans `launcher.json`
+pub fn installed_modpack_ids() -> Vec<(String, String)> {
+    installed_modpacks()
+        .into_iter()
+        .map(|(_, installed)| (installed.manifest.id, installed.modpack_name))
+        .collect()
+}
+
+/// Installe ou met à jour les fichiers d'un modpack sans lancer le jeu.
+///
+/// La progression, l'annulation et la pause passent par les mêmes commandes qu'un lancement.
+#[tauri::command]
+pub async fn install_modpack(
+    app_handle: tauri::AppHandle,
+    state: State<'_, VaultState>,
+    modpack_name: String,
+) -> Result<(), String> {
+    let profile = display_active_account(state)
+        .await
+        .map_err(|e| format!("Failed to get active account: {}", e))?
+        .ok_or_else(|| "No active profile".to_string())?;
+    ensure_modpack_allowed(&profile.username, &modpack_name).await?;
+
+    let launch = LaunchGuard::new(&modpack_name)?;
+    let sync_events = events::spawn_sync_listener(app_handle.clone(), modpack_name.clone());
+    let result = install_modpack_files(
+        &modpack_name,
+        launch.control(),
+        backups::backup_retention(&app_handle),
+        sync_events,
+    )
+    .await;
+    match &result {
+        Ok(()) => tray::settle_status(&app_handle),
+        Err(_) if launch.control().is_cancelled() => {
+            events::emit_launch_cancelled(&app_handle, &modpack_name).await
+        }
+        Err(e) => {
+            events::reset_launch_progress(&app_handle).await;
+            tray::set_status(&app_handle, TrayStatus::Error(e.clone()));
+        }
+    }
+    result
+}
+
+/// Installe ou met à jour les fichiers d'un modpack : commun à `install_modpack` et à la
+/// ligne de commande.
+///
+/// Le loader, les librairies et les assets sont installés par lighty au premier lancement.
+pub async fn install_modpack_files(
+    modpack_name: &str,
+    control: &DownloadControl,
+    backup_retention: usize,
+    sync_events: mpsc::UnboundedSender<SyncEvent>,
+) -> Result<(), String> {
+    let modpack = fetch_modpack(modpack_name).await?;
+    let instance_dir = sync::instance_dir(&modpack.id);
+    if let Err(e) = sync_modpack_files(
+        &modpack,
+        modpack_name,
+        control,
+        backup_retention,
+        sync_events,
+    )
+    .await
+    {
+        if control.is_cancelled() {
+            return Err(launch_cancelled(&instance_dir, modpack_name));
+        }
+        return Err(format!("Failed to install modpack files: {}", e));
+    }
+    Ok(())
+}
+
+/// Vérifie une instance installée contre son manifest installé, sans réseau.
+///
+/// Retourne les fichiers absents ou modifiés (les `ignoredFiles` présents ne comptent pas).
+pub async fn verify_modpack(modpack_name: &str) -> Result<Vec<String>, String> {
+    let (instance_dir, installed) = installed_modpacks()
+        .into_iter()
+        .find(|(_, installed)| installed.modpack_name == modpack_name)
+        .ok_or_else(|| format!("Modpack '{}' is not installed", modpack_name))?;
+
+    let manifest = installed.manifest;
+    let files = manifest
+        .files_info
+        .iter()
+        .map(|file| SyncFile {
+            urls: vec![file.url.clone()],
+            path: file.path.clone(),
+            sha1: file.hash.clone(),
+            size: file.size,
+        })
+        .collect();
+    let ignored = manifest.ignored_files.unwrap_or_default();
+    let plan = sync::plan_sync(&instance_dir, files, &ignored, &[]).await?;
+    Ok(plan.pending_paths().map(str::to_string).collect())
+}
+
+/// Mises à jour trouvées au démarrage. La vérification peut finir avant que le frontend
+/// n'écoute `lighty://modpack-update-available` : il les récupère avec
+/// `take_pending_modpack_updates`.
+static PENDING_MODPACK_UPDATES: Lazy<Mutex<Vec<ModpackUpdatePayload>>> =
+    Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Vérifie les mises à jour des modpacks installés et émet
+/// `lighty://modpack-update-available` pour chacun de ceux qui ont changé (au démarrage).
+pub async fn notify_modpack_updates(app_handle: tauri::AppHandle) {
+    for modpack_name in installed_modpack_names() {
+        match check_modpack_updates(modpack_name.clone()).await {
+            Ok(update) if update.has_update => {
+                tracing::info!("Update available for modpack '{}'", modpack_name);
+                PENDING_MODPACK_UPDATES.lock().unwrap().push(update.clone());
+                let _ = app_handle.emit("lighty://modpack-update-available", update);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to check updates of '{}': {}", modpack_name, e),
+        }
+    }
+}
+
+/// Retourne (et retire) les mises à jour trouvées au démarrage.
+///
+/// Appelée au montage du frontend et à chaque `lighty://modpack-update-available`.
+#[tauri::command]
+pub fn take_pending_modpack_updates() -> Result<Vec<ModpackUpdatePayload>, String> {
+    Ok(std::mem::take(
+        &mut *PENDING_MODPACK_UPDATES.lock().unwrap(),
+    ))
+}
+
 #[tauri::command]
 pub async fn start_modpack(
     app_handle: tauri::AppHandle,
@@ -190,6 +1010,9 @@ pub async fn start_modpack(
     event_bus: State<'_, EventBus>,
     modpack_name: String,
 ) -> Result<String, String> {
+    // Permet `cancel_launch` / `pause_downloads` jusqu'au démarrage du jeu
+    let launch = LaunchGuard::new(&modpack_name)?;
+
     let (instance_exit_tx, instance_exit_rx) = tokio::sync::oneshot::channel::<Option<i32>>();
 
     let mut receiver = event_bus.subscribe();
@@ -203,7 +1026,7 @@ pub async fn start_modpack(
                         ConsoleStream::Stdout => "[GAME]",
                         ConsoleStream::Stderr => "[ERR]",
                     };
-                    println!("{} {}", prefix, e.line);
+                    println!("{} {}", prefix, redact::redact(&e.line));
                 }
                 Event::InstanceExited(e) => {
                     println!("\n⚠ Instance exited with code: {:?}", e.exit_code);
@@ -215,8 +1038,6 @@ pub async fn start_modpack(
             }
         }
     });
-    let launcher_dir = AppState::get_project_dirs();
-
     let settings = get_modpack_settings(&app_handle, &modpack_name);
     println!(
         "Loaded settings for modpack '{}': {:?}",
@@ -230,44 +1051,55 @@ pub async fn start_modpack(
 
     println!("profile: {} (uuid: {})", profile.username, profile.uuid);
     println!("Authentication completed.");
+    ensure_modpack_allowed(&profile.username, &modpack_name).await?;
 
-    let modpack_url = format!(
-        "https://raw.githubusercontent.com/tacxtv/miratopia-launcher/refs/heads/config/modpacks/{}/modpack.json",
-        modpack_name,
-    );
-    let modpack = reqwest::get(&modpack_url)
-        .await
-        .map_err(|e| {
-            let msg = format!("Failed to download modpack from {}: {}", modpack_url, e);
-            tracing::error!(%msg);
-            msg
-        })?
-        .json::<ModpackInfo>()
-        .await
-        .map_err(|e| {
-            let msg = format!("Failed to parse modpack JSON from {}: {}", modpack_url, e);
-            tracing::error!(%msg);
-            msg
-        })?;
-
-    let loader_type = match modpack
-        .modloader_info
-        .get(0)
-        .map(|m| m.loader_type.as_str())
-    {
-        Some("fabric") => Loader::Fabric,
-        Some("forge") => Loader::Forge,
-        Some("neoforge") => Loader::NeoForge,
-        Some("quilt") => Loader::Quilt,
-        _ => {
-            return Err(format!(
-                "Unknown loader type in modpack: {:?}",
-                modpack
-                    .modloader_info
-                    .get(0)
-                    .map(|m| m.loader_type.as_str())
-            ))
+    let sync_events = events::spawn_sync_listener(app_handle.clone(), modpack_name.clone());
+    let result = launch_modpack(
+        &modpack_name,
+        &profile,
+        settings,
+        backups::backup_retention(&app_handle),
+        event_bus.inner(),
+        sync_events,
+        launch.control(),
+    )
+    .await;
+    if let Err(e) = &result {
+        if launch.control().is_cancelled() {
+            events::emit_launch_cancelled(&app_handle, &modpack_name).await;
+        } else {
+            events::reset_launch_progress(&app_handle).await;
+            tray::set_status(&app_handle, TrayStatus::Error(e.clone()));
         }
+    }
+    result?;
+
+    // Le jeu est lancé : plus rien à annuler
+    drop(launch);
+
+    let _ = instance_exit_rx.await;
+    Ok(format!("Game {} launched successfully", modpack_name))
+}
+
+/// Synchronise puis lance un modpack : commun à `start_modpack` et à la ligne de commande.
+///
+/// Retourne dès que le jeu est démarré ; la sortie du jeu arrive par `InstanceExited` sur
+/// `event_bus`. Une annulation via `control` nettoie les téléchargements partiels.
+pub async fn launch_modpack(
+    modpack_name: &str,
+    profile: &UserProfile,
+    settings: Settings,
+    backup_retention: usize,
+    event_bus: &EventBus,
+    sync_events: mpsc::UnboundedSender<SyncEvent>,
+    control: &DownloadControl,
+) -> Result<(), String> {
+    let launcher_dir = AppState::get_project_dirs();
+
+    let modpack = fetch_modpack(modpack_name).await?;
+    let (loader_type, loader_version) = match select_loader(&modpack)? {
+        Some(loader) => (Loader::try_from(loader)?, loader.version.as_str()),
+        None => (Loader::Vanilla, ""),
     };
 
     println!("Launching game with modpack: {:?}", modpack);
@@ -275,30 +1107,32 @@ pub async fn start_modpack(
     let mut instance = VersionBuilder::new(
         &modpack.id,
         loader_type,
-        modpack
-            .modloader_info
-            .get(0)
-            .map(|m| m.version.as_str())
-            .expect("Modloader version is required"),
+        loader_version,
         modpack.minecraft_info.version.as_str(),
         launcher_dir,
     );
 
-    let mut mods = Vec::new();
-    for file in &modpack.files_info {
-        if file.path.contains("mods/") {
-            mods.push(Mods {
-                name: file.path.clone(),
-                path: Some(file.path.clone().replace("mods/", "")),
-                url: Some(file.url.clone()),
-                sha1: Some(file.hash.clone()),
-                size: Some(file.size),
-            });
+    // Pas de `with_mods` : la synchronisation des fichiers est seule propriétaire de `mods/`
+
+    // Synchronisation des fichiers du manifest (reprise, pause et annulation)
+    let instance_dir = sync::instance_dir(&modpack.id);
+    if let Err(e) = sync_modpack_files(
+        &modpack,
+        modpack_name,
+        control,
+        backup_retention,
+        sync_events,
+    )
+    .await
+    {
+        if control.is_cancelled() {
+            return Err(launch_cancelled(&instance_dir, modpack_name));
         }
+        let msg = format!("Failed to sync modpack files: {}", e);
+        tracing::error!(%msg);
+        return Err(msg);
     }
 
-    instance = instance.with_mods(mods);
-
     // Stocke l'instance dans la variable globale
     // {
     //     let mut guard = MC_INSTANCE.lock().unwrap();
@@ -313,34 +1147,98 @@ pub async fn start_modpack(
     //     profile.refresh_token,
     // );
 
-    instance
+    // Associe la session qui démarre au modpack et au compte utilisés
+    sessions::set_launch_info(&modpack.id, modpack_name, &profile.username);
+
+    let run = instance
         .launch(
-            &profile,
+            profile,
             settings
                 .java_distribution
                 .unwrap_or(JavaDistribution::Temurin),
         )
-        .with_event_bus(&event_bus.inner().clone())
+        .with_event_bus(event_bus)
         .with_jvm_options()
         .set("Xmx", settings.max_memory.unwrap_or(4096).to_string() + "M")
         .set("Xms", settings.min_memory.unwrap_or(2048).to_string() + "M")
         .done()
-        .run()
-        .await
-        .map_err(|e| {
-            let msg = format!("Launch failed: {:?}", e);
-            tracing::error!(%msg);
-            msg
-        })?;
+        .run();
 
-    let _ = instance_exit_rx.await;
-    Ok(format!("Game {} launched successfully", modpack_name))
+    // Lighty ne peut pas être mis en pause : seule l'annulation interrompt son installation
+    tokio::select! {
+        result = run => {
+            result.map_err(|e| {
+                let msg = format!("Launch failed: {:?}", e);
+                tracing::error!(%msg);
+                msg
+            })?;
+        }
+        _ = control.cancelled() => {
+            return Err(launch_cancelled(&instance_dir, modpack_name));
+        }
+    }
+    Ok(())
+}
+
+/// Nettoie les fichiers partiels après une annulation et la signale au frontend
+async fn cancel_launch_cleanup(
+    app_handle: &tauri::AppHandle,
+    instance_dir: &std::path::Path,
+    modpack_name: &str,
+) -> String {
+    events::emit_launch_cancelled(app_handle, modpack_name).await;
+    launch_cancelled(instance_dir, modpack_name)
+}
+
+/// Supprime les téléchargements partiels d'un lancement annulé et retourne son erreur
+fn launch_cancelled(instance_dir: &std::path::Path, modpack_name: &str) -> String {
+    download::cleanup_partial_files(instance_dir);
+
+    let msg = format!("Launch of '{}' cancelled", modpack_name);
+    tracing::info!(%msg);
+    msg
+}
+
+/// Annule le lancement en cours d'un modpack (synchronisation des fichiers ou installation)
+#[tauri::command]
+pub fn cancel_launch(modpack_name: String) -> Result<(), String> {
+    let control = download::get_launch(&modpack_name)
+        .ok_or_else(|| format!("No launch in progress for '{}'", modpack_name))?;
+    control.cancel();
+    tracing::info!("Cancelling launch of '{}'", modpack_name);
+    Ok(())
+}
+
+/// Met en pause la synchronisation des fichiers du modpack.
+///
+/// Les connexions sont coupées ; `resume_downloads` reprend chaque fichier là où il
+/// s'était arrêté (requête `Range`). L'installation lighty (Java, librairies, assets)
+/// ne peut pas être mise en pause.
+#[tauri::command]
+pub fn pause_downloads(modpack_name: String) -> Result<(), String> {
+    let control = download::get_launch(&modpack_name)
+        .ok_or_else(|| format!("No launch in progress for '{}'", modpack_name))?;
+    control.pause();
+    tracing::info!("Downloads paused for '{}'", modpack_name);
+    Ok(())
+}
+
+/// Reprend la synchronisation des fichiers mise en pause par `pause_downloads`
+#[tauri::command]
+pub fn resume_downloads(modpack_name: String) -> Result<(), String> {
+    let control = download::get_launch(&modpack_name)
+        .ok_or_else(|| format!("No launch in progress for '{}'", modpack_name))?;
+    if control.is_paused() {
+        control.resume();
+        tracing::info!("Downloads resumed for '{}'", modpack_name);
+    }
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn stop_modpack(
     _event_bus: State<'_, EventBus>,
-    _instance_id: String,
+    instance_id: String,
 ) -> Result<String, String> {
     // On extrait l'instance pour ne pas garder le lock pendant l'await
     let instance_opt = {
@@ -357,7 +1255,9 @@ pub async fn stop_modpack(
         }
         Ok("Instance arrêtée".to_string())
     } else {
-        Err("Instance non trouvée".to_string())
+        // Instance lancée sans `MC_INSTANCE` : arrêt par le PID connu
+        running::stop_instance(&instance_id)?;
+        Ok("Instance arrêtée".to_string())
     }
 }
 
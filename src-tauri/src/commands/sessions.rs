@@ -0,0 +1,20 @@
+use crate::types::{ModpackPlayStats, PlaySession};
+use crate::utils::sessions;
+
+/// Nombre de sessions retournées par défaut
+const DEFAULT_RECENT_SESSIONS: usize = 20;
+
+/// Temps de jeu total, nombre de sessions et dernière partie par modpack,
+/// le plus récemment joué d'abord
+#[tauri::command]
+pub fn get_play_stats() -> Result<Vec<ModpackPlayStats>, String> {
+    Ok(sessions::play_stats())
+}
+
+/// Dernières sessions de jeu, la plus récente d'abord
+#[tauri::command]
+pub fn get_recent_sessions(limit: Option<usize>) -> Result<Vec<PlaySession>, String> {
+    Ok(sessions::recent_sessions(
+        limit.unwrap_or(DEFAULT_RECENT_SESSIONS),
+    ))
+}
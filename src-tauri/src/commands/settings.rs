@@ -1,3 +1,7 @@
+use crate::commands::backups;
+use crate::logging;
+use crate::utils::download::{self, DownloadConfig};
+use crate::utils::http::{self, HttpConfig};
 use lighty_launcher::JavaDistribution;
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -7,7 +11,7 @@ use std::sync::Mutex;
 use tauri::{command, AppHandle};
 use tauri_plugin_store::StoreBuilder;
 
-const SETTINGS_STORE: &str = "settings.json";
+pub const SETTINGS_STORE: &str = "settings.json";
 
 /// Paramètres d’un modpack.
 ///
@@ -55,6 +59,147 @@ impl Default for Settings {
     }
 }
 
+/// Comportement de la fenêtre principale quand le jeu démarre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStartBehavior {
+    KeepOpen,
+    Minimize,
+    HideToTray,
+    /// Ferme la fenêtre ; le launcher quitte à la fin du jeu pour ne pas l'orpheliner
+    Close,
+}
+
+/// Paramètres globaux du launcher (clé `launcher` du store), communs à tous les modpacks.
+///
+/// Même convention que `Settings` : `None` signifie “non défini”, `default()` remplit les valeurs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherSettings {
+    /// Niveau de log (`error`, `warn`, `info`, `debug`, `trace`) ou directive `RUST_LOG`
+    pub log_level: Option<String>,
+    /// Nombre de téléchargements simultanés.
+    ///
+    /// Avec `max_retries` et `retry_delay_ms` : appliqué aussitôt aux fichiers des modpacks,
+    /// au prochain démarrage pour Java, les librairies et les assets (téléchargés par lighty).
+    pub max_concurrent_downloads: Option<usize>,
+    /// Nombre de nouvelles tentatives par fichier
+    pub max_retries: Option<u32>,
+    /// Délai (ms) avant la première nouvelle tentative, doublé ensuite
+    pub retry_delay_ms: Option<u64>,
+    /// Débit maximal en octets/s (`None` ou `0` : illimité), fichiers des modpacks seulement :
+    /// Java, les librairies et les assets ne sont pas limités
+    pub bandwidth_limit: Option<u64>,
+    /// Proxy des requêtes du launcher (`http://`, `https://`, `socks5://`) : `launcher.json`
+    /// et fichiers des modpacks, pas les téléchargements de Java, des librairies ni des assets
+    pub proxy_url: Option<String>,
+    /// Chemins de fichiers PEM de certificats racines supplémentaires
+    pub ca_certificates: Option<Vec<String>>,
+    /// Délai de connexion (s)
+    pub connect_timeout_secs: Option<u64>,
+    /// Délai maximal des requêtes courtes (manifests), en secondes
+    pub request_timeout_secs: Option<u64>,
+    /// Nombre de sauvegardes des saves conservées par instance et par origine (manuelle,
+    /// mise à jour, restauration) (`0` : aucune limite)
+    pub backup_retention: Option<usize>,
+    /// Fenêtre principale au démarrage du jeu
+    pub on_game_start: Option<GameStartBehavior>,
+    /// Réafficher la fenêtre principale quand le jeu se ferme
+    pub restore_on_game_exit: Option<bool>,
+}
+
+impl Default for LauncherSettings {
+    fn default() -> Self {
+        Self {
+            log_level: Some(logging::DEFAULT_LOG_LEVEL.to_string()),
+            max_concurrent_downloads: Some(download::DEFAULT_MAX_CONCURRENT_DOWNLOADS),
+            max_retries: Some(download::DEFAULT_MAX_RETRIES),
+            retry_delay_ms: Some(download::DEFAULT_INITIAL_DELAY_MS),
+            bandwidth_limit: None,
+            proxy_url: None,
+            ca_certificates: None,
+            connect_timeout_secs: Some(http::DEFAULT_CONNECT_TIMEOUT_SECS),
+            request_timeout_secs: Some(http::DEFAULT_REQUEST_TIMEOUT_SECS),
+            backup_retention: Some(backups::DEFAULT_BACKUP_RETENTION),
+            on_game_start: Some(GameStartBehavior::HideToTray),
+            restore_on_game_exit: Some(true),
+        }
+    }
+}
+
+/// Bornes acceptées pour les réglages de téléchargement
+const MAX_CONCURRENT_DOWNLOADS_RANGE: std::ops::RangeInclusive<usize> = 1..=64;
+const MAX_RETRIES_LIMIT: u32 = 10;
+
+impl LauncherSettings {
+    /// Configuration des téléchargements correspondante (valeurs par défaut si non définies)
+    pub fn download_config(&self) -> DownloadConfig {
+        DownloadConfig {
+            max_concurrent_downloads: self
+                .max_concurrent_downloads
+                .unwrap_or(download::DEFAULT_MAX_CONCURRENT_DOWNLOADS),
+            max_retries: self.max_retries.unwrap_or(download::DEFAULT_MAX_RETRIES),
+            initial_delay_ms: self
+                .retry_delay_ms
+                .unwrap_or(download::DEFAULT_INITIAL_DELAY_MS),
+            bandwidth_limit: self.bandwidth_limit.filter(|limit| *limit > 0),
+        }
+    }
+
+    /// Configuration du client HTTP partagé correspondante
+    pub fn http_config(&self) -> HttpConfig {
+        HttpConfig {
+            proxy_url: self
+                .proxy_url
+                .as_deref()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(str::to_string),
+            ca_certificates: self
+                .ca_certificates
+                .iter()
+                .flatten()
+                .map(|path| path.trim())
+                .filter(|path| !path.is_empty())
+                .map(std::path::PathBuf::from)
+                .collect(),
+            connect_timeout: std::time::Duration::from_secs(
+                self.connect_timeout_secs
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or(http::DEFAULT_CONNECT_TIMEOUT_SECS),
+            ),
+            request_timeout: std::time::Duration::from_secs(
+                self.request_timeout_secs
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or(http::DEFAULT_REQUEST_TIMEOUT_SECS),
+            ),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(max) = self.max_concurrent_downloads {
+            if !MAX_CONCURRENT_DOWNLOADS_RANGE.contains(&max) {
+                return Err(format!(
+                    "maxConcurrentDownloads must be between {} and {}",
+                    MAX_CONCURRENT_DOWNLOADS_RANGE.start(),
+                    MAX_CONCURRENT_DOWNLOADS_RANGE.end()
+                ));
+            }
+        }
+        if let Some(retries) = self.max_retries {
+            if retries > MAX_RETRIES_LIMIT {
+                return Err(format!("maxRetries must be at most {}", MAX_RETRIES_LIMIT));
+            }
+        }
+        // Vérifie le proxy et les certificats avant de les enregistrer
+        http::build_client(&self.http_config()).map(|_| ())
+    }
+}
+
+/// Cache en mémoire des settings globaux du launcher.
+static LAUNCHER_SETTINGS_CACHE: LazyLock<Mutex<Option<LauncherSettings>>> =
+    LazyLock::new(|| Mutex::new(None));
+
 /// Cache en mémoire des settings, indexé par nom de modpack.
 ///
 /// Objectif : éviter de relire le store à chaque appel.
@@ -136,6 +281,157 @@ pub fn update_modpack_settings(
     Ok(new_settings)
 }
 
+/// Lit les settings globaux du launcher depuis le cache ou, à défaut, depuis le store (clé `launcher`).
+///
+/// Si la clé n’existe pas ou si la désérialisation échoue, on renvoie `LauncherSettings::default()`.
+pub fn get_launcher_settings(app: &AppHandle) -> LauncherSettings {
+    let mut cache = LAUNCHER_SETTINGS_CACHE.lock().unwrap();
+
+    if let Some(settings) = cache.as_ref() {
+        return settings.clone();
+    }
+
+    let store = StoreBuilder::new(app, std::path::Path::new(SETTINGS_STORE))
+        .build()
+        .expect("Erreur lors de la création du store");
+    let settings: LauncherSettings = match store.get("launcher") {
+        Some(val) => serde_json::from_value(val.clone()).unwrap_or_default(),
+        None => LauncherSettings::default(),
+    };
+
+    *cache = Some(settings.clone());
+    settings
+}
+
+/// Lit `settings.json` directement, sans `AppHandle` ni store Tauri (mode ligne de commande) :
+/// settings globaux et settings par modpack, valeurs par défaut si absents ou invalides.
+pub fn read_settings_file(path: &std::path::Path) -> (LauncherSettings, HashMap<String, Settings>) {
+    let store: HashMap<String, serde_json::Value> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let launcher = store
+        .get("launcher")
+        .and_then(|val| serde_json::from_value(val.clone()).ok())
+        .unwrap_or_default();
+    let modpacks: HashMap<String, serde_json::Value> = store
+        .get("modpacks")
+        .and_then(|val| serde_json::from_value(val.clone()).ok())
+        .unwrap_or_default();
+    let modpacks = modpacks
+        .into_iter()
+        .map(|(name, val)| (name, serde_json::from_value(val).unwrap_or_default()))
+        .collect();
+    (launcher, modpacks)
+}
+
+/// Écrit les settings globaux du launcher dans le store et met à jour le cache.
+fn save_launcher_settings(app: &AppHandle, new_settings: &LauncherSettings) -> Result<(), String> {
+    let store = StoreBuilder::new(app, std::path::Path::new(SETTINGS_STORE))
+        .build()
+        .map_err(|e| e.to_string())?;
+    store.set(
+        "launcher",
+        serde_json::to_value(new_settings).map_err(|e| e.to_string())?,
+    );
+    store.save().map_err(|e| e.to_string())?;
+
+    let mut cache = LAUNCHER_SETTINGS_CACHE.lock().unwrap();
+    *cache = Some(new_settings.clone());
+    Ok(())
+}
+
+/// Applique le niveau de log des settings, sauf si `RUST_LOG` est défini.
+pub fn apply_log_level(app: &AppHandle) {
+    if logging::is_env_override() {
+        tracing::info!("RUST_LOG is set, ignoring log level from settings");
+        return;
+    }
+
+    let level = get_launcher_settings(app)
+        .log_level
+        .unwrap_or_else(|| logging::DEFAULT_LOG_LEVEL.to_string());
+    if let Err(e) = logging::set_log_level(&level) {
+        tracing::warn!("Invalid log level in settings ({}), keeping default", e);
+    }
+}
+
+/// Applique les réglages réseau des settings (client HTTP et téléchargements),
+/// pris en compte dès la prochaine requête.
+pub fn apply_network_settings(app: &AppHandle) {
+    apply_network_config(&get_launcher_settings(app));
+}
+
+/// Applique les réglages réseau de `settings` (aussi utilisé sans interface, voir `cli`)
+pub fn apply_network_config(settings: &LauncherSettings) {
+    if let Err(e) = http::configure(settings.http_config()) {
+        tracing::warn!(
+            "Invalid network settings ({}), keeping previous HTTP client",
+            e
+        );
+    }
+    download::set_download_config(settings.download_config());
+}
+
+/// Commande Tauri : retourne les settings globaux du launcher.
+#[command]
+pub fn display_launcher_settings(app: AppHandle) -> Result<LauncherSettings, String> {
+    Ok(get_launcher_settings(&app))
+}
+
+/// Commande Tauri : remplace les settings globaux du launcher (pas de merge, comme pour les modpacks)
+/// et applique immédiatement ceux qui peuvent l’être.
+#[command]
+pub fn update_launcher_settings(
+    app: AppHandle,
+    new_settings: LauncherSettings,
+) -> Result<LauncherSettings, String> {
+    new_settings.validate()?;
+
+    let previous = get_launcher_settings(&app);
+    save_launcher_settings(&app, &new_settings)?;
+
+    if previous.log_level != new_settings.log_level {
+        apply_log_level(&app);
+    }
+    apply_network_settings(&app);
+    if previous.backup_retention != new_settings.backup_retention {
+        backups::prune_all_backups(backups::backup_retention(&app));
+    }
+
+    Ok(new_settings)
+}
+
+/// Commande Tauri : des réglages de téléchargement modifiés attendent un redémarrage
+/// (lighty, qui télécharge Java, les librairies et les assets, n'est configuré qu'au démarrage)
+#[command]
+pub fn is_restart_required() -> Result<bool, String> {
+    Ok(download::lighty_restart_required())
+}
+
+/// Commande Tauri : change le niveau de log à chaud et le persiste dans les settings.
+#[command]
+pub fn set_log_level(app: AppHandle, level: String) -> Result<(), String> {
+    logging::set_log_level(&level)?;
+
+    let mut settings = get_launcher_settings(&app);
+    settings.log_level = Some(level);
+    save_launcher_settings(&app, &settings)
+}
+
+/// Commande Tauri : change le nombre de sauvegardes conservées par instance,
+/// supprime les plus anciennes au-delà et persiste la valeur.
+#[command]
+pub fn set_backup_retention(app: AppHandle, limit: usize) -> Result<(), String> {
+    let mut settings = get_launcher_settings(&app);
+    settings.backup_retention = Some(limit);
+    save_launcher_settings(&app, &settings)?;
+
+    backups::prune_all_backups(limit);
+    Ok(())
+}
+
 /// Reset all settings to the default values (settings.json + memory cache)
 #[command]
 pub fn reset_all_settings(app: AppHandle) -> Result<(), String> {
@@ -148,6 +444,10 @@ pub fn reset_all_settings(app: AppHandle) -> Result<(), String> {
 
     let mut cache = SETTINGS_CACHE.lock().unwrap();
     cache.clear();
+    drop(cache);
+    *LAUNCHER_SETTINGS_CACHE.lock().unwrap() = None;
+    apply_log_level(&app);
+    apply_network_settings(&app);
 
     tracing::info!("All settings have been reset");
     Ok(())
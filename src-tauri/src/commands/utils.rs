@@ -42,6 +42,13 @@ pub fn open_launcher_folder() -> Result<(), String> {
     Ok(())
 }
 
+/// Restart the launcher (settings applied at startup only)
+#[command]
+pub fn restart_launcher(app: AppHandle) -> Result<(), String> {
+    tracing::info!("Restarting launcher on user request");
+    tauri::process::restart(&app.env());
+}
+
 /// Remove the window state cache file (window position/size)
 /// A launcher restart is necessary for the change to take effect.
 #[command]
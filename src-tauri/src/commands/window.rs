@@ -1,27 +1,193 @@
-use tauri::{AppHandle, Manager, Window};
+use crate::commands::settings::{get_launcher_settings, GameStartBehavior};
+use crate::types::ConsoleLinePayload;
+use crate::utils::console_history;
+use crate::utils::running;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, Window, WindowEvent};
+use tauri_plugin_window_state::{AppHandleExt, StateFlags, WindowExt};
 
-pub fn create_console_window(app_handle: AppHandle) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("console") {
+/// Fermeture demandée pendant une partie : le launcher quitte à la fin du dernier jeu
+static EXIT_AFTER_GAME: AtomicBool = AtomicBool::new(false);
+
+/// Ramène la fenêtre principale au premier plan (restaurée, visible, focus).
+/// Sans effet si elle a été fermée.
+pub fn show_main_window(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        tracing::warn!("Main window not found");
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Applique `onGameStart` quand la fenêtre du jeu apparaît
+pub fn apply_game_start_behavior(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+    let behavior = get_launcher_settings(app_handle)
+        .on_game_start
+        .unwrap_or(GameStartBehavior::HideToTray);
+    match behavior {
+        GameStartBehavior::KeepOpen => {}
+        GameStartBehavior::Minimize => {
+            let _ = window.minimize();
+        }
+        GameStartBehavior::HideToTray => {
+            let _ = window.hide();
+        }
+        GameStartBehavior::Close => {
+            // Le processus reste en vie tant que le jeu tourne (console, sortie du jeu)
+            let _ = window.hide();
+            EXIT_AFTER_GAME.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Après la sortie d'un jeu : quitte si une fermeture était en attente,
+/// sinon réaffiche la fenêtre selon `restoreOnGameExit`, une fois le dernier jeu fermé.
+pub fn apply_game_exit_behavior(app_handle: &AppHandle) {
+    if !running::running_instances().is_empty() {
+        return;
+    }
+
+    let visible = app_handle
+        .get_webview_window("main")
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false);
+    // Fenêtre réaffichée entre-temps (tray) : le joueur utilise de nouveau le launcher
+    if EXIT_AFTER_GAME.swap(false, Ordering::SeqCst) && !visible {
+        tracing::info!("🛑 Last game exited, closing the launcher");
+        app_handle.exit(0);
+        return;
+    }
+
+    if get_launcher_settings(app_handle)
+        .restore_on_game_exit
+        .unwrap_or(true)
+    {
+        show_main_window(app_handle);
+    }
+}
+
+/// Fermeture de la fenêtre principale : cachée dans le tray si un jeu tourne.
+///
+/// Retourne `true` si la fermeture doit être empêchée.
+pub fn hide_if_game_running(app_handle: &AppHandle) -> bool {
+    if running::running_instances().is_empty() {
+        return false;
+    }
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    true
+}
+
+/// Sortie du launcher (menu « Quitter ») : différée jusqu'à la fin des jeux en cours
+/// pour ne jamais les orpheliner.
+///
+/// Retourne `true` si la sortie doit être empêchée.
+pub fn defer_exit_while_running(app_handle: &AppHandle) -> bool {
+    if !hide_if_game_running(app_handle) {
+        return false;
+    }
+    tracing::info!("Exit requested while a game is running, waiting for it to close");
+    EXIT_AFTER_GAME.store(true, Ordering::SeqCst);
+    true
+}
+
+/// État des fenêtres mémorisé par le plugin window-state (les décorations restent celles de la config)
+pub fn window_state_flags() -> StateFlags {
+    StateFlags::all() & !StateFlags::DECORATIONS
+}
+
+/// Ouvre la console : toutes les instances en onglets (`console`),
+/// ou une fenêtre par instance (`console-<instance>`) filtrée sur cette instance.
+///
+/// Position et taille sont restaurées et sauvegardées par fenêtre.
+pub fn create_console_window(app_handle: AppHandle, instance: Option<&str>) -> Result<(), String> {
+    let (label, url) = match instance {
+        Some(instance) => (
+            format!("console-{}", window_label_part(instance)),
+            format!("/console?instance={}", encode_query_value(instance)),
+        ),
+        None => ("console".to_string(), "/console".to_string()),
+    };
+
+    if let Some(window) = app_handle.get_webview_window(&label) {
         let _ = window.show();
         let _ = window.set_focus().ok();
         return Ok(());
     }
 
-    let _window = tauri::WebviewWindowBuilder::new(
-        &app_handle,
-        "console",
-        tauri::WebviewUrl::App("/console".into()),
-    )
-    .title("Console - Miratopia Launcher")
-    .inner_size(1200.0, 700.0)
-    .center()
-    .build()
-    .map_err(|e| e.to_string())?;
+    let title = match instance {
+        Some(instance) => format!("Console {} - Miratopia Launcher", instance),
+        None => "Console - Miratopia Launcher".to_string(),
+    };
+    // Cachée jusqu'à la restauration de sa position, pour ne pas la voir se déplacer
+    let window =
+        tauri::WebviewWindowBuilder::new(&app_handle, &label, tauri::WebviewUrl::App(url.into()))
+            .title(title)
+            .inner_size(1200.0, 700.0)
+            .center()
+            .visible(false)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+    if let Err(e) = window.restore_state(window_state_flags()) {
+        tracing::warn!("Failed to restore state of window '{}': {}", label, e);
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+    // Sauvegarde dès la fermeture, sans attendre la sortie du launcher
+    let app = app_handle.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            if let Err(e) = app.save_window_state(window_state_flags()) {
+                tracing::warn!("Failed to save window state: {}", e);
+            }
+        }
+    });
 
     Ok(())
 }
 
+/// Les labels de fenêtre n'acceptent que `a-zA-Z0-9-/:_`
+fn window_label_part(instance: &str) -> String {
+    instance
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+/// Ouvre la console de toutes les instances, ou celle d'une instance
+#[tauri::command]
+pub fn open_console_window(window: Window, instance: Option<String>) -> Result<(), String> {
+    create_console_window(window.app_handle().clone(), instance.as_deref())
+}
+
+/// Sortie console déjà émise (d'une instance ou de toutes), rejouée à l'ouverture d'une console
 #[tauri::command]
-pub fn open_console_window(window: Window) -> Result<(), String> {
-    create_console_window(window.app_handle().clone())
+pub fn get_console_history(instance: Option<String>) -> Result<Vec<ConsoleLinePayload>, String> {
+    Ok(console_history::lines(instance.as_deref()))
 }
@@ -1,13 +1,22 @@
+use crate::runners::tray::{self, TrayStatus};
 use crate::types::*;
+use crate::utils::console_history;
+use crate::utils::log4j::ConsoleParser;
+use crate::utils::progress::ProgressTracker;
+use crate::utils::redact;
+use crate::utils::running;
+use crate::utils::sessions;
+use crate::utils::sync::SyncEvent;
 use lighty_launcher::event::AuthEvent;
 use lighty_launcher::event::CoreEvent;
 use lighty_launcher::event::JavaEvent;
 use lighty_launcher::event::LaunchEvent;
 use lighty_launcher::event::LoaderEvent;
 use lighty_launcher::event::{Event, EventBus};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 use tokio::time::{Duration, Instant};
 use tracing::info;
 
@@ -19,11 +28,16 @@ struct ProgressState {
     install_total: u64,
     install_downloaded: u64,
     current_instance: String,
+    tracker: ProgressTracker,
 }
 
 /// Lanceur d'écoute des événements LightyLauncher
 /// Convertit les événements EventBus en événements Tauri
 pub fn spawn_event_listener(app: AppHandle, event_bus: EventBus) {
+    // État partagé pour total_bytes (aussi utilisé par la synchronisation des modpacks)
+    let progress_state = Arc::new(Mutex::new(ProgressState::default()));
+    app.manage(progress_state.clone());
+
     tauri::async_runtime::spawn(async move {
         let mut receiver = event_bus.subscribe();
 
@@ -31,24 +45,35 @@ pub fn spawn_event_listener(app: AppHandle, event_bus: EventBus) {
         let last_progress_emit = Arc::new(Mutex::new(Instant::now()));
         let throttle_duration = Duration::from_millis(100); // 10 updates/sec max
 
-        // État partagé pour total_bytes
-        let progress_state = Arc::new(Mutex::new(ProgressState::default()));
-
         // Buffer pour console (éviter spam)
         let console_buffer: Arc<Mutex<Vec<ConsoleLinePayload>>> = Arc::new(Mutex::new(Vec::new()));
         let buffer_flush_interval = Duration::from_millis(250);
 
+        // Un parser log4j par processus (les stack traces sont jointes en une seule entrée)
+        let console_parsers: Arc<Mutex<HashMap<u32, ConsoleParser>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+
         // Task pour flush périodique du buffer console
         let console_buffer_clone = console_buffer.clone();
+        let console_parsers_clone = console_parsers.clone();
         let app_clone = app.clone();
         tauri::async_runtime::spawn(async move {
             let mut interval = tokio::time::interval(buffer_flush_interval);
             loop {
                 interval.tick().await;
+                let mut parsers = console_parsers_clone.lock().await;
                 let mut buffer: tokio::sync::MutexGuard<'_, Vec<ConsoleLinePayload>> =
                     console_buffer_clone.lock().await;
+                // Une entrée sans nouvelle ligne depuis un tick entier est considérée complète
+                for parser in parsers.values_mut() {
+                    if let Some(entry) = parser.flush_stale(buffer_flush_interval) {
+                        buffer.push(entry);
+                    }
+                }
+                drop(parsers);
                 if !buffer.is_empty() {
                     let lines = buffer.drain(..).collect::<Vec<_>>();
+                    console_history::record(&lines);
                     let _ = app_clone.emit("lighty://console-output", lines);
                 }
             }
@@ -62,6 +87,7 @@ pub fn spawn_event_listener(app: AppHandle, event_bus: EventBus) {
                         event,
                         &last_progress_emit,
                         &console_buffer,
+                        &console_parsers,
                         &progress_state,
                         throttle_duration,
                     )
@@ -81,6 +107,7 @@ async fn handle_event(
     event: Event,
     last_emit: &Arc<Mutex<Instant>>,
     console_buffer: &Arc<Mutex<Vec<ConsoleLinePayload>>>,
+    console_parsers: &Arc<Mutex<HashMap<u32, ConsoleParser>>>,
     progress_state: &Arc<Mutex<ProgressState>>,
     throttle: Duration,
 ) {
@@ -102,18 +129,34 @@ async fn handle_event(
                 minecraft_version,
                 loader_version,
             } => {
+                progress_state
+                    .lock()
+                    .await
+                    .tracker
+                    .start_phase(DownloadPhase::Loader, 0);
+                let message = format!("Fetching {} {}", loader, loader_version);
+                emit_launch_progress(app, progress_state, message.clone()).await;
+
                 let payload = LaunchStatusPayload {
                     status: LaunchStatus::Initializing,
-                    phase: format!("Fetching {} {}", loader, loader_version),
+                    phase: message,
                     instance_name: minecraft_version,
                     pid: 0,
                 };
                 let _ = app.emit("lighty://launch-status", payload);
             }
             LoaderEvent::DataFetched { loader, .. } => {
+                progress_state
+                    .lock()
+                    .await
+                    .tracker
+                    .complete_phase(DownloadPhase::Loader);
+                let message = format!("{} metadata loaded", loader);
+                emit_launch_progress(app, progress_state, message.clone()).await;
+
                 let payload = LaunchStatusPayload {
                     status: LaunchStatus::Initializing,
-                    phase: format!("{} metadata loaded", loader),
+                    phase: message,
                     instance_name: String::new(),
                     pid: 0,
                 };
@@ -123,10 +166,15 @@ async fn handle_event(
         },
 
         // === CORE EVENTS ===
-        Event::Core(core_event) => handle_core_event(app, core_event).await,
+        Event::Core(core_event) => handle_core_event(app, core_event, progress_state).await,
 
         // === INSTANCE EVENTS ===
         Event::InstanceLaunched(evt) => {
+            running::mark_running(&evt.instance_name, evt.pid);
+            sessions::start(&evt.instance_name);
+            tray::rebuild_menu(app);
+            tray::settle_status(app);
+
             let payload = LaunchStatusPayload {
                 status: LaunchStatus::Running,
                 phase: "Game running".to_string(),
@@ -145,13 +193,37 @@ async fn handle_event(
             };
             let _ = app.emit("lighty://launch-status", payload);
 
-            // Cacher la fenêtre dans le system tray quand le jeu apparaît
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.hide();
-            }
+            crate::commands::window::apply_game_start_behavior(app);
         }
 
         Event::InstanceExited(evt) => {
+            running::mark_exited(&evt.instance_name);
+            sessions::finish(&evt.instance_name, evt.exit_code);
+            tray::rebuild_menu(app);
+            match evt.exit_code {
+                Some(code) if code != 0 => tray::set_status(
+                    app,
+                    TrayStatus::Error(format!(
+                        "{} s'est arrêté (code {})",
+                        evt.instance_name, code
+                    )),
+                ),
+                _ => tray::settle_status(app),
+            }
+
+            // Libérer les dernières entrées console de l'instance
+            let mut parsers = console_parsers.lock().await;
+            let mut buffer = console_buffer.lock().await;
+            parsers.retain(|_, parser| {
+                if parser.instance_name() != evt.instance_name {
+                    return true;
+                }
+                buffer.extend(parser.flush());
+                false
+            });
+            drop(buffer);
+            drop(parsers);
+
             let payload = LaunchStatusPayload {
                 status: LaunchStatus::Exited,
                 phase: format!("Game exited (code: {:?})", evt.exit_code),
@@ -160,34 +232,48 @@ async fn handle_event(
             };
             let _ = app.emit("lighty://launch-status", payload);
 
-            // Afficher la fenêtre du launcher quand le jeu se ferme
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
+            crate::commands::window::apply_game_exit_behavior(app);
         }
 
         // === CONSOLE OUTPUT ===
         Event::ConsoleOutput(evt) => {
             let line = ConsoleLinePayload {
-                instance_name: evt.instance_name,
+                instance_name: evt.instance_name.clone(),
                 pid: evt.pid,
                 stream: match evt.stream {
                     lighty_launcher::event::ConsoleStream::Stdout => "stdout".to_string(),
                     lighty_launcher::event::ConsoleStream::Stderr => "stderr".to_string(),
                 },
-                line: evt.line,
+                // Les arguments de lancement (--accessToken) peuvent être réaffichés par le jeu
+                line: redact::redact(&evt.line),
                 timestamp: evt
                     .timestamp
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_secs(),
+                // Renseignés par le parser log4j
+                level: ConsoleLevel::Info,
+                thread: None,
+                logger: None,
+                message: String::new(),
             };
 
-            console_buffer.lock().await.push(line);
+            let completed = console_parsers
+                .lock()
+                .await
+                .entry(evt.pid)
+                .or_insert_with(|| ConsoleParser::new(evt.instance_name))
+                .push(line);
+
+            if !completed.is_empty() {
+                console_buffer.lock().await.extend(completed);
+            }
         }
 
         Event::InstanceDeleted(evt) => {
+            // Libère les fichiers du store partagé utilisés uniquement par cette instance
+            crate::utils::store::release_instance(&evt.instance_name);
+
             let payload = LaunchStatusPayload {
                 status: LaunchStatus::Exited,
                 phase: "Instance deleted".to_string(),
@@ -209,6 +295,10 @@ async fn handle_event(
                 let _ = app.emit("lighty://launch-status", payload);
             }
             AuthEvent::AuthenticationFailed { provider, error } => {
+                tray::set_status(
+                    app,
+                    TrayStatus::Error("Authentification échouée".to_string()),
+                );
                 let error_payload = ErrorPayload {
                     category: "auth".to_string(),
                     message: format!("Authentication failed: {}", provider),
@@ -242,6 +332,7 @@ async fn handle_java_event(
             let mut state = progress_state.lock().await;
             state.java_total = total_bytes;
             state.java_downloaded = 0;
+            state.tracker.start_phase(DownloadPhase::Java, total_bytes);
             drop(state);
 
             let payload = DownloadProgressPayload {
@@ -252,6 +343,7 @@ async fn handle_java_event(
                 message: format!("Downloading Java {} {}", distribution, version),
                 instance_name: String::new(), // Java est global, pas lié à une instance
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
         }
 
@@ -259,6 +351,7 @@ async fn handle_java_event(
             // Accumuler les bytes téléchargés
             let mut state = progress_state.lock().await;
             state.java_downloaded += bytes;
+            state.tracker.add_bytes(DownloadPhase::Java, bytes);
             let downloaded = state.java_downloaded;
             let total = state.java_total;
             drop(state);
@@ -292,6 +385,7 @@ async fn handle_java_event(
                     "Java download progress: {}/{} bytes ({}%)",
                     downloaded, total, percentage
                 );
+                emit_launch_progress(app, progress_state, payload.message.clone()).await;
                 let _ = app.emit("lighty://download-progress", payload);
             }
         }
@@ -300,6 +394,12 @@ async fn handle_java_event(
             distribution,
             version,
         } => {
+            progress_state
+                .lock()
+                .await
+                .tracker
+                .start_phase(DownloadPhase::Extracting, 0);
+
             let payload = DownloadProgressPayload {
                 phase: DownloadPhase::Extracting,
                 current_bytes: 0,
@@ -308,6 +408,7 @@ async fn handle_java_event(
                 message: format!("Extracting Java {} {}", distribution, version),
                 instance_name: String::new(),
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
         }
 
@@ -315,6 +416,12 @@ async fn handle_java_event(
             files_extracted,
             total_files,
         } => {
+            progress_state.lock().await.tracker.set_progress(
+                DownloadPhase::Extracting,
+                files_extracted as u64,
+                total_files as u64,
+            );
+
             let percentage = if total_files > 0 {
                 ((files_extracted as f64 / total_files as f64) * 100.0) as u8
             } else {
@@ -332,10 +439,18 @@ async fn handle_java_event(
                 ),
                 instance_name: String::new(),
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
         }
 
         JavaEvent::JavaExtractionCompleted { binary_path, .. } => {
+            progress_state
+                .lock()
+                .await
+                .tracker
+                .complete_phase(DownloadPhase::Extracting);
+            emit_launch_progress(app, progress_state, "Java ready".to_string()).await;
+
             let payload = LaunchStatusPayload {
                 status: LaunchStatus::Installing,
                 phase: format!("Java ready: {}", binary_path),
@@ -372,6 +487,7 @@ async fn handle_launch_event(
             state.install_total = total_bytes;
             state.install_downloaded = 0;
             state.current_instance = version.clone();
+            state.tracker.start_phase(DownloadPhase::Game, total_bytes);
             drop(state);
 
             let payload = DownloadProgressPayload {
@@ -382,6 +498,7 @@ async fn handle_launch_event(
                 message: format!("Installing {}", version),
                 instance_name: version.clone(),
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
 
             let status = LaunchStatusPayload {
@@ -397,6 +514,7 @@ async fn handle_launch_event(
             // Accumuler les bytes téléchargés pour chaque fichier
             let mut state = progress_state.lock().await;
             state.install_downloaded += bytes;
+            state.tracker.add_bytes(DownloadPhase::Game, bytes);
             let downloaded = state.install_downloaded;
             let total = state.install_total;
             let instance = state.current_instance.clone();
@@ -431,6 +549,7 @@ async fn handle_launch_event(
                     "Game download progress: {}/{} bytes ({}%)",
                     downloaded, total, percentage
                 );
+                emit_launch_progress(app, progress_state, payload.message.clone()).await;
                 let _ = app.emit("lighty://download-progress", payload);
             }
         }
@@ -439,6 +558,12 @@ async fn handle_launch_event(
             version,
             total_bytes,
         } => {
+            progress_state
+                .lock()
+                .await
+                .tracker
+                .complete_phase(DownloadPhase::Game);
+
             let payload = DownloadProgressPayload {
                 phase: DownloadPhase::Game,
                 current_bytes: total_bytes,
@@ -447,6 +572,7 @@ async fn handle_launch_event(
                 message: format!("Installation complete: {}", version),
                 instance_name: version,
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
         }
 
@@ -461,6 +587,10 @@ async fn handle_launch_event(
         }
 
         LaunchEvent::Launched { version, pid } => {
+            progress_state.lock().await.tracker.finish();
+            emit_launch_progress(app, progress_state, "Game launched".to_string()).await;
+            progress_state.lock().await.current_instance.clear();
+
             let payload = LaunchStatusPayload {
                 status: LaunchStatus::Running,
                 phase: "Game launched".to_string(),
@@ -471,6 +601,11 @@ async fn handle_launch_event(
         }
 
         LaunchEvent::NotLaunched { version, error } => {
+            let mut state = progress_state.lock().await;
+            state.tracker.finish();
+            state.current_instance.clear();
+            drop(state);
+
             let error_payload = ErrorPayload {
                 category: "launch".to_string(),
                 message: format!("Failed to launch {}", version),
@@ -481,6 +616,10 @@ async fn handle_launch_event(
                     .as_secs(),
             };
             let _ = app.emit("lighty://error", error_payload);
+            tray::set_status(
+                app,
+                TrayStatus::Error(format!("Échec du lancement de {}", version)),
+            );
 
             let status = LaunchStatusPayload {
                 status: LaunchStatus::Failed,
@@ -495,13 +634,23 @@ async fn handle_launch_event(
     }
 }
 
-async fn handle_core_event(app: &AppHandle, event: lighty_launcher::event::CoreEvent) {
+async fn handle_core_event(
+    app: &AppHandle,
+    event: lighty_launcher::event::CoreEvent,
+    progress_state: &Arc<Mutex<ProgressState>>,
+) {
     match event {
         CoreEvent::ExtractionStarted {
             archive_type,
             file_count,
             ..
         } => {
+            progress_state
+                .lock()
+                .await
+                .tracker
+                .start_phase(DownloadPhase::Extracting, file_count as u64);
+
             let payload = LaunchStatusPayload {
                 status: LaunchStatus::Installing,
                 phase: format!("Extracting {} ({} files)", archive_type, file_count),
@@ -515,6 +664,12 @@ async fn handle_core_event(app: &AppHandle, event: lighty_launcher::event::CoreE
             files_extracted,
             total_files,
         } => {
+            progress_state.lock().await.tracker.set_progress(
+                DownloadPhase::Extracting,
+                files_extracted as u64,
+                total_files as u64,
+            );
+
             let percentage = if total_files > 0 {
                 ((files_extracted as f64 / total_files as f64) * 100.0) as u8
             } else {
@@ -529,12 +684,19 @@ async fn handle_core_event(app: &AppHandle, event: lighty_launcher::event::CoreE
                 message: format!("Extracting: {} / {} files", files_extracted, total_files),
                 instance_name: String::new(), // Core extraction pas lié à instance spécifique
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
         }
 
         CoreEvent::ExtractionCompleted {
             files_extracted, ..
         } => {
+            progress_state
+                .lock()
+                .await
+                .tracker
+                .complete_phase(DownloadPhase::Extracting);
+
             let payload = DownloadProgressPayload {
                 phase: DownloadPhase::Extracting,
                 current_bytes: files_extracted as u64,
@@ -543,7 +705,154 @@ async fn handle_core_event(app: &AppHandle, event: lighty_launcher::event::CoreE
                 message: format!("Extraction complete: {} files", files_extracted),
                 instance_name: String::new(),
             };
+            emit_launch_progress(app, progress_state, payload.message.clone()).await;
             let _ = app.emit("lighty://download-progress", payload);
         }
     }
 }
+
+/// Émet la progression consolidée du lancement (`lighty://launch-progress`).
+///
+/// Les événements `lighty://download-progress` restent émis par phase ; celui-ci agrège
+/// toutes les phases (pourcentage pondéré, débit, temps restant).
+async fn emit_launch_progress(
+    app: &AppHandle,
+    progress_state: &Arc<Mutex<ProgressState>>,
+    message: String,
+) {
+    let state = progress_state.lock().await;
+    let payload = state.tracker.snapshot(&state.current_instance, message);
+    drop(state);
+    // Une progression terminée laisse la place à l'état des instances
+    if payload.overall_percentage < 100 {
+        tray::set_status(app, TrayStatus::Downloading(payload.overall_percentage));
+    } else {
+        tray::settle_status(app);
+    }
+    let _ = app.emit("lighty://launch-progress", payload);
+}
+
+/// Relaie la progression de la synchronisation des fichiers d'un modpack.
+///
+/// Retourne l'émetteur à passer à `sync::sync_files` ; la tâche s'arrête quand il est relâché.
+pub fn spawn_sync_listener(
+    app: AppHandle,
+    instance_name: String,
+) -> mpsc::UnboundedSender<SyncEvent> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<SyncEvent>();
+
+    tauri::async_runtime::spawn(async move {
+        let progress_state = app.state::<Arc<Mutex<ProgressState>>>().inner().clone();
+        let throttle = Duration::from_millis(100);
+        let mut last_emit = Instant::now();
+
+        while let Some(event) = receiver.recv().await {
+            match event {
+                SyncEvent::Started {
+                    total_bytes,
+                    total_files,
+                } => {
+                    let mut state = progress_state.lock().await;
+                    state.install_total = total_bytes;
+                    state.install_downloaded = 0;
+                    state.current_instance = instance_name.clone();
+                    state
+                        .tracker
+                        .start_phase(DownloadPhase::Modpack, total_bytes);
+                    drop(state);
+
+                    let payload = DownloadProgressPayload {
+                        phase: DownloadPhase::Modpack,
+                        current_bytes: 0,
+                        total_bytes,
+                        percentage: 0,
+                        message: format!("Syncing {} modpack files", total_files),
+                        instance_name: instance_name.clone(),
+                    };
+                    emit_launch_progress(&app, &progress_state, payload.message.clone()).await;
+                    let _ = app.emit("lighty://download-progress", payload);
+                }
+
+                SyncEvent::Progress { bytes } => {
+                    let mut state = progress_state.lock().await;
+                    state.install_downloaded += bytes;
+                    state.tracker.add_bytes(DownloadPhase::Modpack, bytes);
+                    let downloaded = state.install_downloaded;
+                    let total = state.install_total;
+                    drop(state);
+
+                    if last_emit.elapsed() >= throttle {
+                        last_emit = Instant::now();
+
+                        let percentage = if total > 0 {
+                            (((downloaded as f64 / total as f64) * 100.0).min(100.0)) as u8
+                        } else {
+                            0
+                        };
+
+                        let payload = DownloadProgressPayload {
+                            phase: DownloadPhase::Modpack,
+                            current_bytes: downloaded,
+                            total_bytes: total,
+                            percentage,
+                            message: format!(
+                                "Modpack files: {} / {} MB",
+                                downloaded / 1_000_000,
+                                total / 1_000_000
+                            ),
+                            instance_name: instance_name.clone(),
+                        };
+                        emit_launch_progress(&app, &progress_state, payload.message.clone()).await;
+                        let _ = app.emit("lighty://download-progress", payload);
+                    }
+                }
+
+                SyncEvent::Completed { downloaded_files } => {
+                    let mut state = progress_state.lock().await;
+                    state.tracker.complete_phase(DownloadPhase::Modpack);
+                    let total = state.install_total;
+                    drop(state);
+
+                    let payload = DownloadProgressPayload {
+                        phase: DownloadPhase::Modpack,
+                        current_bytes: total,
+                        total_bytes: total,
+                        percentage: 100,
+                        message: format!(
+                            "Modpack files up to date ({} downloaded)",
+                            downloaded_files
+                        ),
+                        instance_name: instance_name.clone(),
+                    };
+                    emit_launch_progress(&app, &progress_state, payload.message.clone()).await;
+                    let _ = app.emit("lighty://download-progress", payload);
+                }
+            }
+        }
+    });
+
+    sender
+}
+
+/// Abandonne la progression en cours (échec avant ou pendant lighty) : le lancement suivant
+/// repart de zéro au lieu de reprendre les phases déjà marquées
+pub async fn reset_launch_progress(app: &AppHandle) {
+    let progress_state = app.state::<Arc<Mutex<ProgressState>>>().inner().clone();
+    let mut state = progress_state.lock().await;
+    state.tracker.reset();
+    state.current_instance.clear();
+}
+
+/// Signale l'annulation d'un lancement et abandonne la progression en cours
+pub async fn emit_launch_cancelled(app: &AppHandle, instance_name: &str) {
+    reset_launch_progress(app).await;
+    tray::settle_status(app);
+
+    let payload = LaunchStatusPayload {
+        status: LaunchStatus::Cancelled,
+        phase: "Launch cancelled".to_string(),
+        instance_name: instance_name.to_string(),
+        pid: 0,
+    };
+    let _ = app.emit("lighty://launch-status", payload);
+}
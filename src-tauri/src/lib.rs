@@ -1,11 +1,13 @@
+pub mod cli;
 mod commands;
 mod events;
+pub mod logging;
 mod runners;
 mod types;
 mod utils;
 
 use lighty_launcher::{core::AppState, event::EventBus};
-use tauri::Manager;
+use tauri::Emitter;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run(_app_state: AppState) -> anyhow::Result<()> {
@@ -19,22 +21,27 @@ pub fn run(_app_state: AppState) -> anyhow::Result<()> {
 
     #[cfg(desktop)]
     {
-        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            let _ = app
-                .get_webview_window("main")
-                .expect("no main window")
-                .set_focus();
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            commands::window::show_main_window(app);
+
+            // `args[0]` est l'exécutable de la seconde instance
+            let args = args.get(1..).unwrap_or_default();
+            // Lien `miratopia://` ouvert alors que le launcher tourne déjà
+            if let Some(url) = utils::deep_link::find_in_args(args) {
+                utils::deep_link::handle(app, &url);
+            }
+            let payload = utils::args::parse_second_instance(args, &cwd);
+            tracing::info!("Second instance started with {:?}", payload);
+            let _ = app.emit("lighty://second-instance", payload);
         }));
         println!("Single instance plugin OK");
+        builder = builder.plugin(tauri_plugin_deep_link::init());
     }
 
     /* Remove native windows decoration to use a custom title bar */
     builder = builder.plugin(
         tauri_plugin_window_state::Builder::new()
-            .with_state_flags(
-                tauri_plugin_window_state::StateFlags::all()
-                    & !tauri_plugin_window_state::StateFlags::DECORATIONS,
-            )
+            .with_state_flags(commands::window::window_state_flags())
             .build(),
     );
     builder = builder.plugin(tauri_plugin_store::Builder::default().build());
@@ -53,38 +60,25 @@ pub fn run(_app_state: AppState) -> anyhow::Result<()> {
     let app_handle = app.handle().clone();
     events::spawn_event_listener(app_handle, event_bus.clone());
 
-    // use std::sync::{Arc, Mutex};
     use tauri::{RunEvent, WindowEvent};
 
-    // // On utilise un Arc<Mutex<bool>> pour suivre si Minecraft est lancé
-    // let is_mc_running: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
-
-    // // Vérifie la présence d'une instance Minecraft au démarrage
-    // let instance_id = "minecraft".to_string(); // ou autre id selon logique
-    // let instance_present = commands::game::get_instance(instance_id.clone()).unwrap_or(false);
-    // println!("Instance présente au démarrage : {}", instance_present);
-
-    app.run(move |_app_handle, event| {
-        match &event {
-            RunEvent::WindowEvent { label, event, .. } => {
-                if label == "main" {
-                    if let WindowEvent::CloseRequested { api: _, .. } = event {
-                        //     // let running = is_mc_running.lock().unwrap();
-                        //     // if *running {
-                        //     //     // Si Minecraft est lancé, on cache la fenêtre
-                        //     //     if let Some(window) = app_handle.get_webview_window("main") {
-                        //     //         let _ = window.hide();
-                        //     //     }
-                        //     //     api.prevent_close();
-                        //     // } else {
-                        //     //     // Sinon, on quitte normalement
-                        //     //     // rien à faire, la fenêtre se ferme
-                        //     // }
-                    }
-                }
+    app.run(move |app_handle, event| match &event {
+        RunEvent::WindowEvent {
+            label,
+            event: WindowEvent::CloseRequested { api, .. },
+            ..
+        } if label == "main" => {
+            // Un jeu en cours garde le launcher dans le tray
+            if commands::window::hide_if_game_running(app_handle) {
+                api.prevent_close();
+            }
+        }
+        RunEvent::ExitRequested { api, .. } => {
+            if commands::window::defer_exit_while_running(app_handle) {
+                api.prevent_exit();
             }
-            _ => {}
         }
+        _ => {}
     });
 
     Ok(())
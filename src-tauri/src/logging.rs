@@ -0,0 +1,162 @@
+use crate::utils::redact::RedactingMakeWriter;
+use once_cell::sync::OnceCell;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{prelude::*, reload, EnvFilter, Registry};
+
+/// Identifiant Tauri (doit rester synchronisé avec `tauri.conf.json`)
+const APP_IDENTIFIER: &str = "fr.miratopia.minecraft-launcher";
+
+/// Préfixe des fichiers de log (`launcher.YYYY-MM-DD.log`)
+pub const LOG_FILE_PREFIX: &str = "launcher";
+pub const LOG_FILE_SUFFIX: &str = "log";
+
+/// Nombre de fichiers journaliers conservés
+const LOG_RETENTION_DAYS: usize = 7;
+
+/// Niveau utilisé tant que les settings ne sont pas chargés (et sans `RUST_LOG`)
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Crates bavardes limitées à `warn` tant que le niveau demandé n'est pas `debug` ou `trace`
+const NOISY_TARGETS: &[&str] = &["lighty", "hyper", "reqwest", "tao", "wry"];
+
+/// Handle permettant de remplacer le filtre à chaud (`set_log_level`)
+static FILTER_HANDLE: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Dossier des logs du launcher.
+///
+/// Le tracing est initialisé avant Tauri, on ne peut donc pas utiliser `app.path().app_log_dir()` :
+/// ce chemin reproduit la même résolution que Tauri pour l'identifiant de l'application.
+pub fn log_dir() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    let base = dirs::home_dir().map(|dir| dir.join("Library/Logs").join(APP_IDENTIFIER));
+
+    #[cfg(not(target_os = "macos"))]
+    let base = dirs::data_local_dir().map(|dir| dir.join(APP_IDENTIFIER).join("logs"));
+
+    base.unwrap_or_else(|| std::env::temp_dir().join("miratopia-launcher"))
+}
+
+/// Liste les fichiers de log du launcher, du plus récent au plus ancien
+pub fn list_log_files() -> Vec<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(log_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .map(|name| {
+                            name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+                        })
+                        .unwrap_or(false)
+                })
+                .filter_map(|path| {
+                    let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+                    Some((modified, path))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Initialise le tracing : sortie d'erreur + fichier journalier dans le dossier de logs.
+///
+/// Le terminal passe par stderr : stdout est réservé aux sorties des sous-commandes
+/// (`list`, `--json`...), et les couleurs ne sont activées que sur un terminal.
+///
+/// Les fichiers sont conservés `LOG_RETENTION_DAYS` jours pour pouvoir remonter
+/// les logs d'une session précédente après un redémarrage.
+/// Les deux sorties passent par `RedactingMakeWriter` : aucun token ni chemin personnel
+/// n'atteint le disque ou le terminal.
+pub fn init_tracing() -> tracing_appender::non_blocking::WorkerGuard {
+    let log_dir = log_dir();
+    let _ = std::fs::create_dir_all(&log_dir);
+
+    let log_file = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(LOG_RETENTION_DAYS)
+        .build(&log_dir)
+        .expect("failed to open log file");
+
+    let (non_blocking, guard) = tracing_appender::non_blocking(log_file);
+
+    // `RUST_LOG` a priorité ; sinon le niveau des settings est appliqué au setup de l'app
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| build_filter(DEFAULT_LOG_LEVEL).expect("invalid default log level"));
+    let (filter, handle) = reload::Layer::new(filter);
+    let _ = FILTER_HANDLE.set(handle);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(RedactingMakeWriter::new(std::io::stderr))
+                .with_ansi(std::io::stderr().is_terminal()),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(RedactingMakeWriter::new(non_blocking))
+                .with_ansi(false),
+        )
+        .init();
+
+    guard
+}
+
+/// Indique si le filtre est imposé par la variable d'environnement `RUST_LOG`
+pub fn is_env_override() -> bool {
+    std::env::var(EnvFilter::DEFAULT_ENV)
+        .map(|value| !value.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Remplace le filtre de tracing sans redémarrage.
+///
+/// `level` est un niveau (`error`, `warn`, `info`, `debug`, `trace`) ou une directive
+/// complète au format `RUST_LOG` (`info,lighty=debug`).
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    let filter = build_filter(level)?;
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "Tracing is not initialized".to_string())?;
+
+    handle
+        .reload(filter)
+        .map_err(|e| format!("Failed to reload log filter: {}", e))?;
+    tracing::info!("Log level set to '{}'", level);
+    Ok(())
+}
+
+fn build_filter(level: &str) -> Result<EnvFilter, String> {
+    let level = level.trim();
+    let parsed = match level.parse::<LevelFilter>() {
+        Ok(parsed) => parsed,
+        // Directive complète : utilisée telle quelle
+        Err(_) => {
+            return EnvFilter::try_new(level)
+                .map_err(|e| format!("Invalid log level '{}': {}", level, e))
+        }
+    };
+
+    let mut directives = vec![parsed.to_string().to_lowercase()];
+    if parsed < LevelFilter::DEBUG {
+        let noisy_level = parsed.min(LevelFilter::WARN).to_string().to_lowercase();
+        directives.extend(
+            NOISY_TARGETS
+                .iter()
+                .map(|target| format!("{}={}", target, noisy_level)),
+        );
+    }
+
+    EnvFilter::try_new(directives.join(","))
+        .map_err(|e| format!("Invalid log level '{}': {}", level, e))
+}
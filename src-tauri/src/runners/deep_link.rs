@@ -0,0 +1,39 @@
+use crate::utils::deep_link;
+
+/// Liens `miratopia://` reçus au démarrage à froid : argv sous Windows et Linux,
+/// événement système sous macOS. Ceux d'une seconde instance passent par `single_instance`.
+pub fn setup(app: &tauri::App) -> tauri::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(url) = deep_link::find_in_args(&args) {
+        deep_link::handle(app.handle(), &url);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_plugin_deep_link::DeepLinkExt;
+
+        if let Ok(Some(urls)) = app.deep_link().get_current() {
+            for url in urls {
+                deep_link::handle(app.handle(), url.as_str());
+            }
+        }
+        let handle = app.handle().clone();
+        app.deep_link().on_open_url(move |event| {
+            for url in event.urls() {
+                deep_link::handle(&handle, url.as_str());
+            }
+        });
+    }
+
+    // En développement, le schéma n'est pas enregistré par l'installeur
+    #[cfg(all(debug_assertions, any(windows, target_os = "linux")))]
+    {
+        use tauri_plugin_deep_link::DeepLinkExt;
+
+        if let Err(e) = app.deep_link().register_all() {
+            tracing::warn!("Failed to register {}:// scheme: {}", deep_link::SCHEME, e);
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
-mod tray;
+#[cfg(desktop)]
+mod deep_link;
+pub mod tray;
 mod vault;
 
 pub fn setup(mut builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
@@ -6,7 +8,15 @@ pub fn setup(mut builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::W
 
     builder.setup(|app| {
         vault::setup(app)?;
+        crate::commands::settings::apply_log_level(app.handle());
+        crate::commands::settings::apply_network_settings(app.handle());
         tray::init(app)?;
+        #[cfg(desktop)]
+        deep_link::setup(app)?;
+
+        tauri::async_runtime::spawn(crate::commands::modpacks::notify_modpack_updates(
+            app.handle().clone(),
+        ));
 
         Ok(())
     })
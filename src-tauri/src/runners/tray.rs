@@ -1,17 +1,78 @@
+use crate::commands::{accounts, modpacks};
+use crate::utils::running;
+use crate::utils::sessions;
+use crate::utils::vault::VaultState;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
 use tauri::{
-    menu::{Menu, MenuItem},
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
 
-pub fn init(app: &tauri::App) -> tauri::Result<()> {
-    let quit_i = MenuItem::with_id(app, "quit", "Quitter", true, None::<&str>)?;
-    let console_i = MenuItem::with_id(app, "console", "Console", true, None::<&str>)?;
+/// Identifiant de l'icône, pour retrouver le tray lors des reconstructions du menu
+pub const TRAY_ID: &str = "main";
+
+/// Nombre de modpacks proposés au lancement rapide
+const RECENT_PACKS_LIMIT: usize = 5;
+
+const LAUNCH_PREFIX: &str = "launch:";
+const STOP_PREFIX: &str = "stop:";
+const ACCOUNT_PREFIX: &str = "account:";
+const CONSOLE_PREFIX: &str = "console:";
+
+const TOOLTIP_TITLE: &str = "Miratopia Launcher";
+const TOOLTIP_ERROR_LEN: usize = 80;
+
+/// État agrégé du launcher affiché par l'icône et l'infobulle
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayStatus {
+    Idle,
+    /// Pourcentage global du lancement ou de l'installation en cours
+    Downloading(u8),
+    /// Instances dont le jeu tourne
+    Running(Vec<String>),
+    Error(String),
+}
+
+impl TrayStatus {
+    fn tooltip(&self) -> String {
+        match self {
+            TrayStatus::Idle => TOOLTIP_TITLE.to_string(),
+            TrayStatus::Downloading(percentage) => {
+                format!("{} - Téléchargement {} %", TOOLTIP_TITLE, percentage)
+            }
+            TrayStatus::Running(instances) => {
+                format!("{} - En jeu : {}", TOOLTIP_TITLE, instances.join(", "))
+            }
+            TrayStatus::Error(message) => {
+                // Les infobulles sont tronquées par le système (128 caractères sous Windows)
+                let message: String = message.chars().take(TOOLTIP_ERROR_LEN).collect();
+                format!("{} - Erreur : {}", TOOLTIP_TITLE, message)
+            }
+        }
+    }
 
-    let menu = Menu::with_items(app, &[&console_i, &quit_i])?;
+    /// Couleur (RGB) de la pastille ajoutée à l'icône, aucune au repos
+    fn badge_color(&self) -> Option<[u8; 3]> {
+        match self {
+            TrayStatus::Idle => None,
+            TrayStatus::Downloading(_) => Some([59, 130, 246]),
+            TrayStatus::Running(_) => Some([34, 197, 94]),
+            TrayStatus::Error(_) => Some([239, 68, 68]),
+        }
+    }
+}
+
+static TRAY_STATUS: Lazy<Mutex<TrayStatus>> = Lazy::new(|| Mutex::new(TrayStatus::Idle));
 
-    TrayIconBuilder::new()
+pub fn init(app: &tauri::App) -> tauri::Result<()> {
+    let menu = build_menu(app.handle())?;
+
+    TrayIconBuilder::with_id(TRAY_ID)
         .icon(app.default_window_icon().unwrap().clone())
+        .tooltip(TrayStatus::Idle.tooltip())
         .on_tray_icon_event(|tray, event| match event {
             TrayIconEvent::Click {
                 button: MouseButton::Left,
@@ -43,13 +104,217 @@ pub fn init(app: &tauri::App) -> tauri::Result<()> {
                     let _ = window.show();
                     let _ = window.set_focus().ok();
                 }
-                let _ = crate::commands::window::create_console_window(app.clone());
+                let _ = crate::commands::window::create_console_window(app.clone(), None);
+            }
+            "open_folder" => {
+                if let Err(e) = modpacks::open_modpacks_folder() {
+                    tracing::warn!("Failed to open modpacks folder: {}", e);
+                }
             }
-            _ => {
-                tracing::warn!("⁉️ Menu item <{:?}> not handled", event.id);
+            id => {
+                if let Some(modpack_name) = id.strip_prefix(LAUNCH_PREFIX) {
+                    // Le lancement passe par le frontend, qui suit sa progression
+                    crate::commands::window::show_main_window(app);
+                    let _ = app.emit("lighty://tray-launch", modpack_name.to_string());
+                } else if let Some(instance_name) = id.strip_prefix(STOP_PREFIX) {
+                    if let Err(e) = running::stop_instance(instance_name) {
+                        tracing::warn!("Failed to stop '{}' from tray: {}", instance_name, e);
+                    }
+                } else if let Some(instance_name) = id.strip_prefix(CONSOLE_PREFIX) {
+                    let _ = crate::commands::window::create_console_window(
+                        app.clone(),
+                        Some(instance_name),
+                    );
+                } else if let Some(account) = id.strip_prefix(ACCOUNT_PREFIX) {
+                    match accounts::switch_active_account(
+                        app.clone(),
+                        app.state::<VaultState>(),
+                        account,
+                    ) {
+                        Ok(()) => {
+                            let _ =
+                                app.emit("lighty://active-account-changed", account.to_string());
+                        }
+                        Err(e) => tracing::warn!("Failed to switch account from tray: {}", e),
+                    }
+                } else {
+                    tracing::warn!("⁉️ Menu item <{:?}> not handled", event.id);
+                }
             }
         })
         .build(app)?;
 
     Ok(())
 }
+
+/// Reconstruit le menu après un changement de compte ou des instances en cours
+pub fn rebuild_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let result = build_menu(app).and_then(|menu| tray.set_menu(Some(menu)));
+    if let Err(e) = result {
+        tracing::warn!("Failed to rebuild tray menu: {}", e);
+    }
+}
+
+/// Met à jour l'icône et l'infobulle ; l'icône n'est redessinée que si l'état change de nature
+pub fn set_status(app: &AppHandle, status: TrayStatus) {
+    let previous = {
+        let mut current = TRAY_STATUS.lock().unwrap();
+        if *current == status {
+            return;
+        }
+        std::mem::replace(&mut *current, status.clone())
+    };
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    if let Err(e) = tray.set_tooltip(Some(status.tooltip())) {
+        tracing::warn!("Failed to update tray tooltip: {}", e);
+    }
+    if std::mem::discriminant(&previous) != std::mem::discriminant(&status) {
+        let Some(icon) = app.default_window_icon() else {
+            return;
+        };
+        let icon = match status.badge_color() {
+            Some(color) => with_badge(icon, color),
+            None => icon.clone(),
+        };
+        if let Err(e) = tray.set_icon(Some(icon)) {
+            tracing::warn!("Failed to update tray icon: {}", e);
+        }
+    }
+}
+
+/// Revient à « en jeu » ou « au repos » selon les instances en cours
+pub fn settle_status(app: &AppHandle) {
+    let running: Vec<String> = running::running_instances()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    if running.is_empty() {
+        set_status(app, TrayStatus::Idle);
+    } else {
+        set_status(app, TrayStatus::Running(running));
+    }
+}
+
+/// Copie de l'icône avec une pastille colorée dans le coin inférieur droit
+fn with_badge(icon: &Image<'_>, color: [u8; 3]) -> Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+
+    let radius = width.min(height) as f64 / 4.0;
+    let (center_x, center_y) = (width as f64 - radius, height as f64 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f64 + 0.5 - center_x;
+            let dy = y as f64 + 0.5 - center_y;
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+/// Menu : lancement rapide, arrêt des instances, changement de compte, dossier, console
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    let running = running::running_instances();
+
+    // Sessions et jeux en cours sont indexés par identifiant d'instance (`manifest.id`) ;
+    // le lancement, lui, passe par le nom du modpack dans `launcher.json`
+    let installed = modpacks::installed_modpack_ids();
+    let display_name = |instance_id: &str| {
+        installed
+            .iter()
+            .find(|(id, _)| id == instance_id)
+            .map_or(instance_id.to_string(), |(_, name)| name.clone())
+    };
+
+    // Les modpacks joués le plus récemment d'abord
+    let stats = sessions::play_stats();
+    let mut recent_packs: Vec<&(String, String)> = installed
+        .iter()
+        .filter(|(id, _)| !running.iter().any(|(instance, _)| instance == id))
+        .collect();
+    recent_packs.sort_by_key(|(id, _)| {
+        let last_played = stats
+            .iter()
+            .find(|stats| &stats.modpack == id)
+            .map(|stats| stats.last_played);
+        std::cmp::Reverse(last_played.unwrap_or(0))
+    });
+    for (_, name) in recent_packs.into_iter().take(RECENT_PACKS_LIMIT) {
+        let id = format!("{}{}", LAUNCH_PREFIX, name);
+        let label = format!("Lancer {}", name);
+        menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+    for (instance_id, _) in &running {
+        let name = display_name(instance_id);
+        let id = format!("{}{}", STOP_PREFIX, instance_id);
+        let label = format!("Arrêter {}", name);
+        menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+        let id = format!("{}{}", CONSOLE_PREFIX, instance_id);
+        let label = format!("Console {}", name);
+        menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+    if !menu.items()?.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    menu.append(&accounts_submenu(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "open_folder",
+        "Ouvrir le dossier",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "console",
+        "Console",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "quit",
+        "Quitter",
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(menu)
+}
+
+/// Sous-menu des comptes du vault, le compte actif coché
+fn accounts_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let state = app.state::<VaultState>();
+    let names = accounts::account_names(&state).unwrap_or_else(|e| {
+        tracing::warn!("Failed to list accounts for tray: {}", e);
+        Vec::new()
+    });
+    let active = accounts::active_account_name(&state).ok().flatten();
+
+    let submenu = Submenu::with_id(app, "accounts", "Changer de compte", !names.is_empty())?;
+    for name in names {
+        let checked = active.as_deref() == Some(name.as_str());
+        let id = format!("{}{}", ACCOUNT_PREFIX, name);
+        submenu.append(&CheckMenuItem::with_id(
+            app,
+            id,
+            &name,
+            true,
+            checked,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(submenu)
+}
@@ -1,8 +1,6 @@
-use crate::utils::vault::{init_vault_if_needed, VaultState};
+use crate::utils::vault::{init_vault_if_needed, VaultState, VAULT_PASSWORD};
 use tauri::Manager;
 
-const VAULT_PASSWORD: &str = "dev-vault-password";
-
 /// Setup the vault plugin and initialize the vault if needed
 pub fn setup(app: &tauri::App) -> tauri::Result<()> {
     let salt_path = app
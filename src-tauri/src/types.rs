@@ -1,15 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 /// Phase de téléchargement/installation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DownloadPhase {
     Idle,
+    Modpack,
     Java,
     Loader,
+    /// Client, librairies et assets (lighty ne les distingue pas)
     Game,
-    Assets,
-    Libraries,
     Extracting,
 }
 
@@ -24,6 +24,30 @@ pub struct DownloadProgressPayload {
     pub instance_name: String,
 }
 
+/// Avancement d'une phase dans la progression globale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseProgress {
+    pub phase: DownloadPhase,
+    pub current: u64,
+    pub total: u64,
+    pub percentage: u8,
+    pub weight: u32,
+    pub completed: bool,
+}
+
+/// Progression consolidée d'un lancement (toutes phases confondues)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchProgressPayload {
+    pub instance_name: String,
+    pub current_phase: DownloadPhase,
+    pub overall_percentage: u8,
+    pub phases: Vec<PhaseProgress>,
+    pub completed_phases: Vec<DownloadPhase>,
+    pub bytes_per_second: u64,
+    pub eta_seconds: Option<u64>,
+    pub message: String,
+}
+
 /// Statut de lancement
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -36,6 +60,7 @@ pub enum LaunchStatus {
     Launched,
     Exited,
     Failed,
+    Cancelled,
 }
 
 /// Événement de changement de statut
@@ -47,7 +72,37 @@ pub struct LaunchStatusPayload {
     pub pid: u32,
 }
 
+/// Niveau de sévérité d'une entrée de console (log4j)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl ConsoleLevel {
+    /// Convertit un niveau log4j (`INFO`, `WARN`, ...) en `ConsoleLevel`
+    pub fn from_log4j(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "TRACE" => Some(Self::Trace),
+            "DEBUG" => Some(Self::Debug),
+            "INFO" => Some(Self::Info),
+            "WARN" | "WARNING" => Some(Self::Warn),
+            "ERROR" => Some(Self::Error),
+            "FATAL" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+}
+
 /// Ligne de console
+///
+/// `line` contient le texte brut (les stack traces sont jointes avec `\n`),
+/// `thread`, `logger` et `message` sont extraits du format log4j quand il est reconnu.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsoleLinePayload {
     pub instance_name: String,
@@ -55,6 +110,10 @@ pub struct ConsoleLinePayload {
     pub stream: String, // "stdout" | "stderr"
     pub line: String,
     pub timestamp: u64,
+    pub level: ConsoleLevel,
+    pub thread: Option<String>,
+    pub logger: Option<String>,
+    pub message: String,
 }
 
 /// Erreur
@@ -65,3 +124,180 @@ pub struct ErrorPayload {
     pub details: Option<String>,
     pub timestamp: u64,
 }
+
+/// Taille (octets) d'une instance par catégorie, retournée par `get_modpack_disk_usage`.
+///
+/// Les fichiers liés depuis le store partagé sont comptés dans chaque instance qui les utilise.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub mods: u64,
+    pub config: u64,
+    pub saves: u64,
+    pub logs: u64,
+    pub libraries: u64,
+    pub assets: u64,
+    pub runtime: u64,
+    pub other: u64,
+    pub total: u64,
+}
+
+/// Sauvegarde des saves d'une instance, retournée par `list_saves_backups`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavesBackup {
+    /// Nom de l'archive, à passer à `restore_saves_backup`
+    pub name: String,
+    pub modpack_id: String,
+    /// `manual`, `update` ou `restore`
+    pub reason: String,
+    pub size: u64,
+    /// Date de création (secondes depuis l'epoch)
+    pub created_at: u64,
+}
+
+/// Fichier ajouté, supprimé ou modifié entre deux versions d'un modpack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+}
+
+/// Changement de version (loader ou Minecraft) ; `None` : pas de loader (vanilla)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Différences entre le manifest installé et le manifest distant,
+/// retourné par `check_modpack_updates` et émis sur `lighty://modpack-update-available`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackUpdatePayload {
+    pub modpack_name: String,
+    pub modpack_id: String,
+    /// `false` si aucun manifest installé n'est connu (premier lancement à venir)
+    pub installed: bool,
+    pub has_update: bool,
+    pub mods_added: Vec<FileChange>,
+    pub mods_removed: Vec<FileChange>,
+    pub mods_updated: Vec<FileChange>,
+    /// Autres fichiers (config, resource packs...) ajoutés, supprimés ou modifiés
+    pub other_files_changed: usize,
+    pub loader_change: Option<VersionChange>,
+    pub minecraft_change: Option<VersionChange>,
+    pub changelog: Option<String>,
+}
+
+/// Gravité d'un problème de manifest : une erreur rend le manifest invalide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestSeverity {
+    Error,
+    Warning,
+}
+
+/// Problème trouvé dans un `modpack.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestIssue {
+    pub severity: ManifestSeverity,
+    /// Entrée concernée (`files[3]`, `loaders`, `minecraft.version`...)
+    pub entry: Option<String>,
+    pub message: String,
+}
+
+/// Résultat de `validate_modpack_manifest`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestReport {
+    pub schema_version: u64,
+    pub valid: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub issues: Vec<ManifestIssue>,
+}
+
+impl ManifestReport {
+    pub fn new(schema_version: u64, issues: Vec<ManifestIssue>) -> Self {
+        let mut report = Self {
+            schema_version,
+            valid: true,
+            error_count: 0,
+            warning_count: 0,
+            issues: Vec::new(),
+        };
+        report.extend(issues);
+        report
+    }
+
+    pub fn extend(&mut self, issues: Vec<ManifestIssue>) {
+        self.issues.extend(issues);
+        self.error_count = self
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == ManifestSeverity::Error)
+            .count();
+        self.warning_count = self.issues.len() - self.error_count;
+        self.valid = self.error_count == 0;
+    }
+}
+
+/// Action d'un lien `miratopia://`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeepLinkAction {
+    Launch,
+    Install,
+    Server,
+}
+
+/// Lien `miratopia://` reçu, émis sur `lighty://deep-link` en attente de confirmation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepLinkPayload {
+    pub action: DeepLinkAction,
+    /// Nom du modpack (`launch`, `install`) ou adresse `host[:port]` (`server`)
+    pub target: String,
+    pub url: String,
+}
+
+/// Arguments d'une seconde instance du launcher, émis sur `lighty://second-instance`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecondInstancePayload {
+    /// Modpack à sélectionner
+    pub modpack: Option<String>,
+    /// `--console` : ouvrir la console
+    pub console: bool,
+    /// Fichiers à importer (chemins absolus, existants)
+    pub files: Vec<String>,
+    /// Dossier courant de la seconde instance
+    pub cwd: String,
+}
+
+/// Session de jeu terminée, enregistrée dans l'historique local
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaySession {
+    /// Identifiant de l'instance (`manifest.id`)
+    pub modpack: String,
+    /// Nom du modpack dans `launcher.json`, absent des sessions enregistrées avant son ajout
+    #[serde(default)]
+    pub modpack_name: Option<String>,
+    /// Compte utilisé, inconnu si le lancement ne l'a pas renseigné
+    pub account: Option<String>,
+    /// Début et fin (secondes depuis l'epoch)
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub exit_code: Option<i32>,
+    /// Sortie en erreur sans arrêt demandé par le joueur
+    pub crashed: bool,
+}
+
+/// Temps de jeu cumulé d'un modpack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackPlayStats {
+    /// Identifiant de l'instance (`manifest.id`)
+    pub modpack: String,
+    /// Nom du modpack dans `launcher.json` lors de la dernière session qui le connaît
+    pub modpack_name: Option<String>,
+    pub total_seconds: u64,
+    pub session_count: u32,
+    /// Fin de la dernière session (secondes depuis l'epoch)
+    pub last_played: u64,
+}
@@ -0,0 +1,56 @@
+use crate::types::SecondInstancePayload;
+use crate::utils::deep_link;
+use std::path::Path;
+
+/// Arguments d'une seconde instance (sans l'exécutable) :
+/// - `--console` : ouvrir la console ;
+/// - `--pack <id>` / `--pack=<id>`, ou un nom de modpack seul : modpack à sélectionner ;
+/// - chemins de fichiers existants (relatifs au dossier de la seconde instance) : à importer.
+///
+/// Les liens `miratopia://` sont traités par `deep_link` ; les options inconnues sont ignorées.
+pub fn parse_second_instance<S: AsRef<str>>(args: &[S], cwd: &str) -> SecondInstancePayload {
+    let mut payload = SecondInstancePayload {
+        modpack: None,
+        console: false,
+        files: Vec::new(),
+        cwd: cwd.to_string(),
+    };
+
+    let mut args = args.iter().map(|arg| arg.as_ref().trim());
+    while let Some(arg) = args.next() {
+        if arg.is_empty() || deep_link::is_deep_link(arg) {
+            continue;
+        }
+        if arg == "--console" {
+            payload.console = true;
+        } else if arg == "--pack" {
+            set_modpack(&mut payload, args.next().unwrap_or_default());
+        } else if let Some(pack) = arg.strip_prefix("--pack=") {
+            set_modpack(&mut payload, pack);
+        } else if arg.starts_with('-') {
+            tracing::debug!("Ignoring unknown second instance argument '{}'", arg);
+        } else if let Some(file) = existing_file(arg, cwd) {
+            payload.files.push(file);
+        } else {
+            set_modpack(&mut payload, arg);
+        }
+    }
+    payload
+}
+
+fn set_modpack(payload: &mut SecondInstancePayload, name: &str) {
+    if deep_link::is_modpack_name(name) {
+        payload.modpack = Some(name.to_string());
+    } else {
+        tracing::warn!("Ignoring invalid modpack '{}' from second instance", name);
+    }
+}
+
+/// Chemin absolu d'un fichier existant ; un chemin relatif l'est au dossier de la seconde instance
+fn existing_file(arg: &str, cwd: &str) -> Option<String> {
+    let path = Path::new(cwd).join(arg);
+    path.is_file()
+        .then(|| path.canonicalize().ok())
+        .flatten()
+        .map(|path| path.to_string_lossy().into_owned())
+}
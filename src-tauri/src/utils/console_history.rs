@@ -0,0 +1,36 @@
+use crate::types::ConsoleLinePayload;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Lignes conservées par instance, les plus anciennes sont oubliées au-delà
+const MAX_LINES_PER_INSTANCE: usize = 5000;
+
+/// Sortie console récente, par nom d'instance : une fenêtre de console ouverte en cours de
+/// partie la rejoue avant de suivre `lighty://console-output`
+static HISTORY: Lazy<Mutex<HashMap<String, VecDeque<ConsoleLinePayload>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Ajoute les lignes émises sur `lighty://console-output`
+pub fn record(lines: &[ConsoleLinePayload]) {
+    let mut history = HISTORY.lock().unwrap();
+    for line in lines {
+        let instance = history.entry(line.instance_name.clone()).or_default();
+        if instance.len() == MAX_LINES_PER_INSTANCE {
+            instance.pop_front();
+        }
+        instance.push_back(line.clone());
+    }
+}
+
+/// Lignes conservées d'une instance, ou de toutes (dans l'ordre d'émission par instance)
+pub fn lines(instance: Option<&str>) -> Vec<ConsoleLinePayload> {
+    let history = HISTORY.lock().unwrap();
+    match instance {
+        Some(instance) => history
+            .get(instance)
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default(),
+        None => history.values().flatten().cloned().collect(),
+    }
+}
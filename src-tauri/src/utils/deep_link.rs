@@ -0,0 +1,142 @@
+use crate::types::{DeepLinkAction, DeepLinkPayload};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Schéma des liens du launcher (`plugins.deep-link` de `tauri.conf.json`)
+pub const SCHEME: &str = "miratopia";
+
+/// Longueur maximale d'un nom de modpack ou d'un hôte dans un lien
+const MAX_TARGET_LEN: usize = 253;
+
+/// Dernier lien reçu, en attente de confirmation. Au démarrage à froid, le lien arrive
+/// avant que le frontend n'écoute `lighty://deep-link` : il le récupère avec
+/// `take_pending_deep_link`.
+static PENDING_DEEP_LINK: Lazy<Mutex<Option<DeepLinkPayload>>> = Lazy::new(|| Mutex::new(None));
+
+/// Premier argument qui est un lien `miratopia://` (argv au démarrage ou d'une seconde instance)
+pub fn find_in_args<S: AsRef<str>>(args: &[S]) -> Option<String> {
+    args.iter()
+        .map(|arg| arg.as_ref().trim())
+        .find(|arg| is_deep_link(arg))
+        .map(str::to_string)
+}
+
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.get(..SCHEME.len() + 1)
+        .map(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", SCHEME)))
+        .unwrap_or(false)
+}
+
+/// Décode `miratopia://launch/<pack>`, `miratopia://install/<pack>` ou
+/// `miratopia://server/<host>[:port]`. Le lien vient de l'extérieur : la cible est validée
+/// strictement et aucune action n'est exécutée ici.
+pub fn parse(url: &str) -> Result<DeepLinkPayload, String> {
+    if !is_deep_link(url) {
+        return Err(format!("Not a {}:// link", SCHEME));
+    }
+    let rest = url[SCHEME.len() + 1..].trim_start_matches('/');
+    // Requête et fragment ignorés ; les navigateurs ajoutent parfois un `/` final
+    let rest = rest
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+
+    let (action, target) = rest
+        .split_once('/')
+        .ok_or_else(|| format!("Invalid link '{}': expected <action>/<target>", url))?;
+    let action = match action.to_ascii_lowercase().as_str() {
+        "launch" => DeepLinkAction::Launch,
+        "install" => DeepLinkAction::Install,
+        "server" => DeepLinkAction::Server,
+        other => return Err(format!("Unknown link action '{}'", other)),
+    };
+
+    let valid = match action {
+        DeepLinkAction::Launch | DeepLinkAction::Install => is_modpack_name(target),
+        DeepLinkAction::Server => is_server_address(target),
+    };
+    if !valid {
+        return Err(format!("Invalid target '{}' in link '{}'", target, url));
+    }
+
+    Ok(DeepLinkPayload {
+        action,
+        target: target.to_string(),
+        url: url.to_string(),
+    })
+}
+
+/// Nom de dossier de modpack : pas de séparateur, pas de `..`, pas de fichier caché
+pub fn is_modpack_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_TARGET_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Port Minecraft par défaut, implicite dans `host`
+const DEFAULT_SERVER_PORT: u16 = 25565;
+
+/// `host` ou `host:port`
+pub fn is_server_address(address: &str) -> bool {
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (address, None),
+    };
+    let valid_host = !host.is_empty()
+        && host.len() <= MAX_TARGET_LEN
+        && !host.starts_with(['.', '-'])
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'));
+    let valid_port = port.map_or(true, |port| port.parse::<u16>().is_ok_and(|p| p > 0));
+    valid_host && valid_port
+}
+
+/// Deux adresses `host[:port]` désignent-elles le même serveur ? (casse et port par défaut ignorés)
+pub fn same_server(a: &str, b: &str) -> bool {
+    fn normalize(address: &str) -> (String, u16) {
+        let address = address.trim().to_ascii_lowercase();
+        match address.rsplit_once(':') {
+            Some((host, port)) => (
+                host.trim_end_matches('.').to_string(),
+                port.parse().unwrap_or(DEFAULT_SERVER_PORT),
+            ),
+            None => (
+                address.trim_end_matches('.').to_string(),
+                DEFAULT_SERVER_PORT,
+            ),
+        }
+    }
+    normalize(a) == normalize(b)
+}
+
+/// Reçoit un lien (argv, seconde instance ou système) : il est gardé en attente et envoyé
+/// au frontend, qui demande confirmation avant d'appeler la commande correspondante.
+pub fn handle(app: &AppHandle, url: &str) {
+    let payload = match parse(url) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("Ignoring deep link: {}", e);
+            return;
+        }
+    };
+    tracing::info!(
+        "Deep link received: {:?} {}",
+        payload.action,
+        payload.target
+    );
+
+    *PENDING_DEEP_LINK.lock().unwrap() = Some(payload.clone());
+    crate::commands::window::show_main_window(app);
+    let _ = app.emit("lighty://deep-link", payload);
+}
+
+/// Retire le lien en attente (affiché ou traité par le frontend)
+pub fn take_pending() -> Option<DeepLinkPayload> {
+    PENDING_DEEP_LINK.lock().unwrap().take()
+}
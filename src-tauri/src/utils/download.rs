@@ -0,0 +1,510 @@
+use lighty_launcher::launch::{init_downloader_config, DownloaderConfig};
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::{header::RANGE, StatusCode};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// Message d'erreur renvoyé quand un téléchargement est annulé
+pub const CANCELLED: &str = "Download cancelled";
+
+/// Extension des fichiers en cours de téléchargement
+const PARTIAL_EXTENSION: &str = "part";
+
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 16;
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_INITIAL_DELAY_MS: u64 = 200;
+
+/// Réglages réseau des téléchargements, issus des settings du launcher.
+///
+/// Relus au début de chaque téléchargement du launcher : une modification s'applique au
+/// suivant. Lighty ne les reçoit qu'au démarrage, voir `lighty_restart_required`.
+#[derive(Debug, Clone)]
+pub struct DownloadConfig {
+    pub max_concurrent_downloads: usize,
+    /// Nombre de tentatives (reprises via `Range`) par fichier
+    pub max_retries: u32,
+    /// Délai avant la première nouvelle tentative, doublé à chaque échec
+    pub initial_delay_ms: u64,
+    /// Débit maximal en octets/s des téléchargements du launcher (`None` : illimité) ;
+    /// Java, les librairies et les assets, téléchargés par lighty, ne sont pas limités
+    pub bandwidth_limit: Option<u64>,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_delay_ms: DEFAULT_INITIAL_DELAY_MS,
+            bandwidth_limit: None,
+        }
+    }
+}
+
+static DOWNLOAD_CONFIG: Lazy<RwLock<DownloadConfig>> =
+    Lazy::new(|| RwLock::new(DownloadConfig::default()));
+
+/// Concurrence, tentatives et délai transmis à lighty. Rien ne garantit qu'il prenne en compte
+/// une seconde initialisation : il n'est configuré qu'une fois, au démarrage.
+static LIGHTY_CONFIG: OnceCell<(usize, u32, u64)> = OnceCell::new();
+
+/// Prochain instant où la bande passante est libre (partagé par les téléchargements du launcher)
+static BANDWIDTH_NEXT_SLOT: Lazy<Mutex<Instant>> = Lazy::new(|| Mutex::new(Instant::now()));
+
+pub fn download_config() -> DownloadConfig {
+    DOWNLOAD_CONFIG.read().unwrap().clone()
+}
+
+/// Applique les réglages réseau au launcher et à lighty.
+///
+/// Le launcher les applique au téléchargement suivant ; lighty ne les reçoit qu'au premier
+/// appel (démarrage), une modification ne le concerne qu'au prochain démarrage. Lighty ne
+/// reçoit que la concurrence et les tentatives : le plafond de débit ne s'applique qu'aux
+/// téléchargements faits par le launcher (fichiers des modpacks), pas à Java, aux
+/// librairies ni aux assets.
+pub fn set_download_config(config: DownloadConfig) {
+    LIGHTY_CONFIG.get_or_init(|| {
+        init_downloader_config(DownloaderConfig {
+            max_concurrent_downloads: config.max_concurrent_downloads,
+            max_retries: config.max_retries,
+            initial_delay_ms: config.initial_delay_ms,
+            ..Default::default()
+        });
+        lighty_values(&config)
+    });
+
+    tracing::info!(
+        "Download settings: {} concurrent, {} retries, {} ms delay, limit {:?} B/s",
+        config.max_concurrent_downloads,
+        config.max_retries,
+        config.initial_delay_ms,
+        config.bandwidth_limit
+    );
+    *DOWNLOAD_CONFIG.write().unwrap() = config;
+    if lighty_restart_required() {
+        tracing::warn!("Download settings changed, Java/library/asset downloads need a restart");
+    }
+}
+
+/// Les réglages actuels diffèrent-ils de ceux reçus par lighty au démarrage ?
+pub fn lighty_restart_required() -> bool {
+    LIGHTY_CONFIG
+        .get()
+        .is_some_and(|applied| *applied != lighty_values(&download_config()))
+}
+
+fn lighty_values(config: &DownloadConfig) -> (usize, u32, u64) {
+    (
+        config.max_concurrent_downloads,
+        config.max_retries,
+        config.initial_delay_ms,
+    )
+}
+
+/// Attend le temps nécessaire pour respecter le plafond de débit
+async fn throttle(bytes: u64, bandwidth_limit: Option<u64>) {
+    let limit = match bandwidth_limit {
+        Some(limit) if limit > 0 => limit,
+        _ => return,
+    };
+
+    let cost = Duration::from_secs_f64(bytes as f64 / limit as f64);
+    let ready_at = {
+        let mut next_slot = BANDWIDTH_NEXT_SLOT.lock().unwrap();
+        // Pas de crédit accumulé pendant les périodes d'inactivité
+        let start = (*next_slot).max(Instant::now());
+        *next_slot = start + cost;
+        *next_slot
+    };
+    tokio::time::sleep_until(ready_at.into()).await;
+}
+
+/// Attend avant une nouvelle tentative (délai exponentiel) ; erreur si annulé
+async fn backoff(
+    attempts: u32,
+    config: &DownloadConfig,
+    control: &DownloadControl,
+) -> Result<(), String> {
+    let factor = 1u64 << attempts.saturating_sub(1).min(10);
+    let delay = Duration::from_millis(config.initial_delay_ms.saturating_mul(factor));
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => Ok(()),
+        _ = control.cancelled() => Err(CANCELLED.to_string()),
+    }
+}
+
+/// Contrôle d'un lancement en cours : annulation et pause des téléchargements.
+///
+/// Le clone partage le même état : le pipeline garde une copie, les commandes
+/// `cancel_launch` / `pause_downloads` / `resume_downloads` agissent sur celle du registre.
+#[derive(Clone)]
+pub struct DownloadControl {
+    cancel: CancellationToken,
+    paused: watch::Sender<bool>,
+}
+
+impl Default for DownloadControl {
+    fn default() -> Self {
+        Self {
+            cancel: CancellationToken::new(),
+            paused: watch::Sender::new(false),
+        }
+    }
+}
+
+impl DownloadControl {
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+        // Débloque les téléchargements en pause pour qu'ils constatent l'annulation
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Future résolue à l'annulation
+    pub async fn cancelled(&self) {
+        self.cancel.cancelled().await
+    }
+
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Attend la reprise si les téléchargements sont en pause ; erreur si annulé
+    pub async fn checkpoint(&self) -> Result<(), String> {
+        let mut paused = self.paused.subscribe();
+        loop {
+            if self.is_cancelled() {
+                return Err(CANCELLED.to_string());
+            }
+            if !*paused.borrow_and_update() {
+                return Ok(());
+            }
+            tokio::select! {
+                _ = self.cancel.cancelled() => {}
+                changed = paused.changed() => {
+                    if changed.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Future résolue dès que les téléchargements passent en pause
+    async fn paused(&self) {
+        let mut paused = self.paused.subscribe();
+        let _ = paused.wait_for(|paused| *paused).await;
+    }
+}
+
+/// Lancements en cours, indexés par nom de modpack
+static ACTIVE_LAUNCHES: Lazy<Mutex<HashMap<String, DownloadControl>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Instance (`id` du manifest) de chaque lancement, connue une fois le manifest récupéré :
+/// le nom du modpack peut différer de l'id qui nomme le dossier
+static LAUNCH_INSTANCES: Lazy<Mutex<HashMap<String, String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Enregistre un lancement ; échoue si le modpack est déjà en cours de lancement
+pub fn register_launch(modpack_name: &str) -> Result<DownloadControl, String> {
+    let mut launches = ACTIVE_LAUNCHES.lock().unwrap();
+    if launches.contains_key(modpack_name) {
+        return Err(format!("Modpack '{}' is already launching", modpack_name));
+    }
+
+    let control = DownloadControl::default();
+    launches.insert(modpack_name.to_string(), control.clone());
+    Ok(control)
+}
+
+pub fn unregister_launch(modpack_name: &str) {
+    ACTIVE_LAUNCHES.lock().unwrap().remove(modpack_name);
+    LAUNCH_INSTANCES.lock().unwrap().remove(modpack_name);
+}
+
+/// Associe le lancement de `modpack_name` au dossier d'instance qu'il synchronise
+pub fn bind_launch_instance(modpack_name: &str, instance_id: &str) {
+    LAUNCH_INSTANCES
+        .lock()
+        .unwrap()
+        .insert(modpack_name.to_string(), instance_id.to_string());
+}
+
+/// Un lancement utilise-t-il cette instance, qu'il soit enregistré sous son id ou sous
+/// un nom de modpack différent ?
+pub fn is_instance_launching(instance_id: &str) -> bool {
+    if ACTIVE_LAUNCHES.lock().unwrap().contains_key(instance_id) {
+        return true;
+    }
+    LAUNCH_INSTANCES
+        .lock()
+        .unwrap()
+        .values()
+        .any(|id| id == instance_id)
+}
+
+/// Retire le lancement du registre quand il est relâché (fin ou erreur de `start_modpack`)
+pub struct LaunchGuard {
+    modpack_name: String,
+    control: DownloadControl,
+}
+
+impl LaunchGuard {
+    pub fn new(modpack_name: &str) -> Result<Self, String> {
+        Ok(Self {
+            modpack_name: modpack_name.to_string(),
+            control: register_launch(modpack_name)?,
+        })
+    }
+
+    pub fn control(&self) -> &DownloadControl {
+        &self.control
+    }
+}
+
+impl Drop for LaunchGuard {
+    fn drop(&mut self) {
+        unregister_launch(&self.modpack_name);
+    }
+}
+
+pub fn get_launch(modpack_name: &str) -> Option<DownloadControl> {
+    ACTIVE_LAUNCHES.lock().unwrap().get(modpack_name).cloned()
+}
+
+/// Chemin du fichier partiel associé à `dest` (`mods/foo.jar` -> `mods/foo.jar.part`)
+pub fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(PARTIAL_EXTENSION);
+    dest.with_file_name(name)
+}
+
+/// Supprime les fichiers partiels laissés dans `dir` (après une annulation)
+pub fn cleanup_partial_files(dir: &Path) -> usize {
+    let mut removed = 0;
+    for entry in walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+    {
+        let is_partial = entry
+            .path()
+            .extension()
+            .map(|ext| ext == PARTIAL_EXTENSION)
+            .unwrap_or(false);
+        if is_partial && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        tracing::info!("Removed {} partial file(s) in {:?}", removed, dir);
+    }
+    removed
+}
+
+/// SHA-1 (hexadécimal) d'un fichier
+pub async fn file_sha1(path: &Path) -> std::io::Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha1::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Télécharge `url` vers `dest` en passant par un fichier `.part`.
+///
+/// - un `.part` existant est repris avec une requête `Range` (pause, erreur réseau, redémarrage) ;
+/// - la pause coupe la connexion, la reprise relance une requête `Range` ;
+/// - l'annulation supprime le `.part` ;
+/// - le SHA-1 est vérifié avant de renommer le fichier final.
+///
+/// `on_bytes` reçoit le nombre d'octets ajoutés au fichier à chaque chunk.
+pub async fn download_file<F>(
+    client: &reqwest::Client,
+    config: &DownloadConfig,
+    url: &str,
+    dest: &Path,
+    expected_sha1: Option<&str>,
+    control: &DownloadControl,
+    mut on_bytes: F,
+) -> Result<(), String>
+where
+    F: FnMut(u64),
+{
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+
+    let part = partial_path(dest);
+    let result = download_to_partial(client, config, url, &part, control, &mut on_bytes).await;
+    if let Err(e) = result {
+        if control.is_cancelled() {
+            let _ = tokio::fs::remove_file(&part).await;
+        }
+        return Err(e);
+    }
+
+    if let Some(expected) = expected_sha1 {
+        let actual = file_sha1(&part)
+            .await
+            .map_err(|e| format!("Failed to hash '{}': {}", part.display(), e))?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(format!(
+                "SHA-1 mismatch for {} (expected {}, got {})",
+                url, expected, actual
+            ));
+        }
+    }
+
+    tokio::fs::rename(&part, dest)
+        .await
+        .map_err(|e| format!("Failed to move '{}': {}", dest.display(), e))
+}
+
+async fn download_to_partial<F>(
+    client: &reqwest::Client,
+    config: &DownloadConfig,
+    url: &str,
+    part: &Path,
+    control: &DownloadControl,
+    on_bytes: &mut F,
+) -> Result<(), String>
+where
+    F: FnMut(u64),
+{
+    let mut attempts = 0;
+    let mut reported: u64 = 0;
+
+    'attempts: loop {
+        control.checkpoint().await?;
+
+        let offset = tokio::fs::metadata(part)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        // Octets d'un `.part` laissé par une session précédente
+        if offset > reported {
+            on_bytes(offset - reported);
+            reported = offset;
+        }
+
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let response = tokio::select! {
+            response = request.send() => response,
+            _ = control.cancelled() => return Err(CANCELLED.to_string()),
+        };
+        let mut response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                attempts += 1;
+                if attempts > config.max_retries {
+                    return Err(format!("Failed to download {}: {}", url, e));
+                }
+                tracing::warn!("Download of {} failed ({}), retrying", url, e);
+                backoff(attempts, config, control).await?;
+                continue 'attempts;
+            }
+        };
+
+        let append = match response.status() {
+            StatusCode::PARTIAL_CONTENT => true,
+            // Le `.part` est déjà complet (ou invalide) : on repart de zéro
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                let _ = tokio::fs::remove_file(part).await;
+                attempts += 1;
+                if attempts > config.max_retries {
+                    return Err(format!("Failed to download {}: invalid range", url));
+                }
+                continue 'attempts;
+            }
+            status if status.is_success() => false,
+            status => {
+                return Err(format!("Failed to download {}: HTTP {}", url, status));
+            }
+        };
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(part)
+            .await
+            .map_err(|e| format!("Failed to open '{}': {}", part.display(), e))?;
+        // Position dans le `.part` : seuls les octets au-delà de `reported` sont remontés
+        let mut written = if append { offset } else { 0 };
+
+        loop {
+            tokio::select! {
+                chunk = response.chunk() => match chunk {
+                    Ok(Some(bytes)) => {
+                        file.write_all(&bytes)
+                            .await
+                            .map_err(|e| format!("Failed to write '{}': {}", part.display(), e))?;
+                        written += bytes.len() as u64;
+                        if written > reported {
+                            on_bytes(written - reported);
+                            reported = written;
+                        }
+                        throttle(bytes.len() as u64, config.bandwidth_limit).await;
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        let _ = file.flush().await;
+                        attempts += 1;
+                        if attempts > config.max_retries {
+                            return Err(format!("Failed to download {}: {}", url, e));
+                        }
+                        tracing::warn!("Download of {} interrupted ({}), resuming", url, e);
+                        backoff(attempts, config, control).await?;
+                        continue 'attempts;
+                    }
+                },
+                // La pause coupe la connexion : la reprise se fera avec une requête `Range`
+                _ = control.paused() => {
+                    let _ = file.flush().await;
+                    continue 'attempts;
+                }
+                _ = control.cancelled() => return Err(CANCELLED.to_string()),
+            }
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write '{}': {}", part.display(), e))?;
+        return Ok(());
+    }
+}
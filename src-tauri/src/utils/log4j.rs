@@ -0,0 +1,263 @@
+use crate::types::{ConsoleLevel, ConsoleLinePayload};
+use std::time::{Duration, Instant};
+
+/// Champs extraits d'une ligne (ou d'un événement XML) log4j
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedLine {
+    level: ConsoleLevel,
+    thread: Option<String>,
+    logger: Option<String>,
+    message: String,
+}
+
+/// Entrée en attente : elle peut encore recevoir les lignes d'une stack trace
+struct PendingEntry {
+    payload: ConsoleLinePayload,
+    updated_at: Instant,
+}
+
+/// Parser de la sortie console d'une instance Minecraft.
+///
+/// Un parser est créé par processus : il garde l'entrée courante en attente tant que
+/// des lignes de continuation (stack trace, `Caused by:` ...) arrivent, et accumule
+/// les événements XML log4j (`<log4j:Event>`) qui s'étalent sur plusieurs lignes.
+pub struct ConsoleParser {
+    instance_name: String,
+    pending: Option<PendingEntry>,
+    xml_buffer: Option<String>,
+}
+
+impl ConsoleParser {
+    pub fn new(instance_name: impl Into<String>) -> Self {
+        Self {
+            instance_name: instance_name.into(),
+            pending: None,
+            xml_buffer: None,
+        }
+    }
+
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    /// Ajoute une ligne brute et retourne les entrées terminées.
+    ///
+    /// `raw` doit avoir `line` renseigné ; les champs `level`, `thread`, `logger`
+    /// et `message` sont recalculés par le parser.
+    pub fn push(&mut self, raw: ConsoleLinePayload) -> Vec<ConsoleLinePayload> {
+        let mut completed = Vec::new();
+
+        // Événement XML en cours d'accumulation
+        if let Some(buffer) = self.xml_buffer.as_mut() {
+            buffer.push('\n');
+            buffer.push_str(&raw.line);
+            if raw.line.contains("</log4j:Event>") {
+                let xml = self.xml_buffer.take().unwrap_or_default();
+                completed.extend(self.start_entry(raw, &xml, parse_xml_event(&xml)));
+            }
+            return completed;
+        }
+
+        let trimmed = raw.line.trim_start();
+        if trimmed.starts_with("<log4j:Event") {
+            if trimmed.contains("</log4j:Event>") {
+                let xml = raw.line.clone();
+                completed.extend(self.start_entry(raw, &xml, parse_xml_event(&xml)));
+            } else {
+                self.xml_buffer = Some(raw.line);
+            }
+            return completed;
+        }
+
+        if let Some(parsed) = parse_pattern_line(&raw.line) {
+            let line = raw.line.clone();
+            completed.extend(self.start_entry(raw, &line, Some(parsed)));
+            return completed;
+        }
+
+        if is_continuation(&raw.line) {
+            if let Some(pending) = self.pending.as_mut() {
+                pending.payload.line.push('\n');
+                pending.payload.line.push_str(&raw.line);
+                pending.payload.message.push('\n');
+                pending.payload.message.push_str(&raw.line);
+                pending.updated_at = Instant::now();
+                return completed;
+            }
+        }
+
+        // Ligne hors format log4j (sortie de la JVM, println des mods...)
+        let line = raw.line.clone();
+        completed.extend(self.start_entry(raw, &line, None));
+        completed
+    }
+
+    /// Libère l'entrée en attente si elle n'a pas reçu de nouvelle ligne depuis `max_age`
+    pub fn flush_stale(&mut self, max_age: Duration) -> Option<ConsoleLinePayload> {
+        match &self.pending {
+            Some(pending) if pending.updated_at.elapsed() >= max_age => self.flush(),
+            _ => None,
+        }
+    }
+
+    /// Libère l'entrée en attente, quel que soit son âge
+    pub fn flush(&mut self) -> Option<ConsoleLinePayload> {
+        self.pending.take().map(|pending| pending.payload)
+    }
+
+    /// Démarre une nouvelle entrée et retourne la précédente si elle existait
+    fn start_entry(
+        &mut self,
+        mut raw: ConsoleLinePayload,
+        line: &str,
+        parsed: Option<ParsedLine>,
+    ) -> Option<ConsoleLinePayload> {
+        let parsed = parsed.unwrap_or_else(|| ParsedLine {
+            level: if raw.stream == "stderr" {
+                ConsoleLevel::Error
+            } else {
+                ConsoleLevel::Info
+            },
+            thread: None,
+            logger: None,
+            message: line.to_string(),
+        });
+
+        raw.line = line.to_string();
+        raw.level = parsed.level;
+        raw.thread = parsed.thread;
+        raw.logger = parsed.logger;
+        raw.message = parsed.message;
+
+        let previous = self.flush();
+        self.pending = Some(PendingEntry {
+            payload: raw,
+            updated_at: Instant::now(),
+        });
+        previous
+    }
+}
+
+/// Parse une ligne au format `PatternLayout` de Minecraft :
+///
+/// - `[12:34:56] [Render thread/INFO]: message` (vanilla, fabric)
+/// - `[12:34:56] [main/INFO] [net.minecraft.Foo/]: message` (forge, neoforge)
+/// - `[12:34:56] [main/INFO] (FabricLoader) message` (fabric loader)
+fn parse_pattern_line(line: &str) -> Option<ParsedLine> {
+    let (_time, rest) = take_delimited(line.trim_start(), '[', ']')?;
+    let (thread_level, rest) = take_delimited(rest.trim_start(), '[', ']')?;
+
+    let (thread, level) = thread_level.rsplit_once('/')?;
+    let level = ConsoleLevel::from_log4j(level)?;
+
+    let mut rest = rest.trim_start();
+    let mut logger = None;
+    if let Some((name, after)) = take_delimited(rest, '[', ']') {
+        let name = name.split('/').next().unwrap_or(name);
+        logger = Some(name.to_string());
+        rest = after;
+    } else if let Some((name, after)) = take_delimited(rest, '(', ')') {
+        logger = Some(name.to_string());
+        rest = after;
+    }
+
+    let message = rest.strip_prefix(':').unwrap_or(rest).trim_start();
+
+    Some(ParsedLine {
+        level,
+        thread: Some(thread.to_string()).filter(|t| !t.is_empty()),
+        logger: logger.filter(|l| !l.is_empty()),
+        message: message.to_string(),
+    })
+}
+
+/// Parse un événement `XMLLayout` log4j complet :
+///
+/// ```xml
+/// <log4j:Event logger="net.minecraft.client.Minecraft" timestamp="..." level="INFO" thread="Render thread">
+///   <log4j:Message><![CDATA[Setting user: Player]]></log4j:Message>
+/// </log4j:Event>
+/// ```
+fn parse_xml_event(xml: &str) -> Option<ParsedLine> {
+    let start = xml.find("<log4j:Event")?;
+    let header_end = start + xml[start..].find('>')?;
+    let header = &xml[start..header_end];
+
+    let level = xml_attribute(header, "level")
+        .and_then(|l| ConsoleLevel::from_log4j(&l))
+        .unwrap_or(ConsoleLevel::Info);
+
+    let mut message = xml_element_text(xml, "log4j:Message").unwrap_or_default();
+    if let Some(throwable) = xml_element_text(xml, "log4j:Throwable") {
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(throwable.trim_end());
+    }
+
+    Some(ParsedLine {
+        level,
+        thread: xml_attribute(header, "thread"),
+        logger: xml_attribute(header, "logger"),
+        message,
+    })
+}
+
+/// Indique si la ligne prolonge l'entrée précédente (stack trace Java)
+fn is_continuation(line: &str) -> bool {
+    if line.is_empty() {
+        return false;
+    }
+    if line.starts_with(char::is_whitespace) {
+        return true;
+    }
+
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("Caused by:") || trimmed.starts_with("Suppressed:") {
+        return true;
+    }
+
+    // En-tête d'exception : `java.lang.IllegalStateException: ...`
+    let class_name = trimmed.split(':').next().unwrap_or_default();
+    !class_name.contains(' ')
+        && class_name.contains('.')
+        && (class_name.ends_with("Exception") || class_name.ends_with("Error"))
+}
+
+/// Retourne le contenu entre `open` et `close` en début de chaîne, ainsi que le reste
+fn take_delimited(input: &str, open: char, close: char) -> Option<(&str, &str)> {
+    let inner = input.strip_prefix(open)?;
+    let end = inner.find(close)?;
+    Some((&inner[..end], &inner[end + close.len_utf8()..]))
+}
+
+fn xml_attribute(header: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {}=\"", name);
+    let start = header.find(&pattern)? + pattern.len();
+    let end = start + header[start..].find('"')?;
+    Some(unescape_xml(&header[start..end]))
+}
+
+fn xml_element_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    let content = xml[start..end].trim();
+
+    let content = content
+        .strip_prefix("<![CDATA[")
+        .and_then(|c| c.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| unescape_xml(content));
+    Some(content)
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,409 @@
+use crate::types::{ManifestIssue, ManifestReport, ManifestSeverity};
+use crate::utils::deep_link;
+use crate::utils::http;
+use crate::utils::paths::check_relative_path;
+use crate::utils::store;
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::task::JoinSet;
+
+/// Version du schéma de `modpack.json` produite par les outils actuels
+pub const CURRENT_SCHEMA_VERSION: u64 = 1;
+
+/// Versions du schéma que ce launcher sait lire
+pub const SUPPORTED_SCHEMA_VERSIONS: &[u64] = &[1];
+
+/// Types de loader reconnus (voir `TryFrom<&LoaderModpackInfo> for Loader`)
+const LOADER_TYPES: &[&str] = &["vanilla", "fabric", "forge", "neoforge", "quilt"];
+
+/// Dossiers de premier niveau attendus dans une instance
+const KNOWN_TOP_LEVEL_DIRS: &[&str] = &[
+    "mods",
+    "config",
+    "defaultconfigs",
+    "resourcepacks",
+    "shaderpacks",
+    "kubejs",
+    "scripts",
+    "global_packs",
+];
+
+/// Requêtes `HEAD` simultanées lors de la vérification des URLs
+const MAX_CONCURRENT_HEAD_REQUESTS: usize = 8;
+
+/// Accumule les problèmes trouvés pendant la validation
+struct Issues(Vec<ManifestIssue>);
+
+impl Issues {
+    fn error(&mut self, entry: Option<String>, message: impl Into<String>) {
+        self.push(ManifestSeverity::Error, entry, message);
+    }
+
+    fn warning(&mut self, entry: Option<String>, message: impl Into<String>) {
+        self.push(ManifestSeverity::Warning, entry, message);
+    }
+
+    fn push(
+        &mut self,
+        severity: ManifestSeverity,
+        entry: Option<String>,
+        message: impl Into<String>,
+    ) {
+        self.0.push(ManifestIssue {
+            severity,
+            entry,
+            message: message.into(),
+        });
+    }
+}
+
+/// Valide un `modpack.json` (schéma, chemins, doublons, hashes, URLs)
+pub fn validate_manifest(manifest: &Value) -> ManifestReport {
+    let mut issues = Issues(Vec::new());
+
+    let schema_version = check_schema_version(manifest, &mut issues);
+    if manifest.is_object() {
+        check_metadata(manifest, &mut issues);
+        check_loaders(manifest, &mut issues);
+        check_files(manifest, &mut issues);
+    } else {
+        issues.error(None, "manifest must be a JSON object");
+    }
+
+    ManifestReport::new(schema_version, issues.0)
+}
+
+/// Valide un manifest puis compare la taille annoncée par chaque URL (`HEAD`) à `size`
+pub async fn validate_manifest_with_urls(manifest: &Value) -> ManifestReport {
+    let mut report = validate_manifest(manifest);
+    let mut issues = Issues(Vec::new());
+    check_remote_sizes(manifest, &mut issues).await;
+    report.extend(issues.0);
+    report
+}
+
+/// Charge un manifest depuis un fichier local, une URL ou le nom d'un modpack publié
+pub async fn load_manifest(source: &str) -> Result<Value, String> {
+    let path = std::path::Path::new(source);
+    let content = if path.is_file() {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?
+    } else {
+        let url = if source.starts_with("http://") || source.starts_with("https://") {
+            source.to_string()
+        } else {
+            format!(
+                "https://raw.githubusercontent.com/tacxtv/miratopia-launcher/refs/heads/config/modpacks/{}/modpack.json",
+                source
+            )
+        };
+        http::get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to download manifest from {}: {}", url, e))?
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read manifest from {}: {}", url, e))?
+    };
+
+    serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+fn check_schema_version(manifest: &Value, issues: &mut Issues) -> u64 {
+    match manifest.get("schemaVersion") {
+        None => {
+            issues.warning(
+                Some("schemaVersion".to_string()),
+                format!(
+                    "missing, assuming version {} (add \"schemaVersion\": {})",
+                    CURRENT_SCHEMA_VERSION, CURRENT_SCHEMA_VERSION
+                ),
+            );
+            CURRENT_SCHEMA_VERSION
+        }
+        Some(value) => match value.as_u64() {
+            Some(version) if SUPPORTED_SCHEMA_VERSIONS.contains(&version) => version,
+            Some(version) => {
+                issues.error(
+                    Some("schemaVersion".to_string()),
+                    format!(
+                        "unsupported version {} (supported: {:?})",
+                        version, SUPPORTED_SCHEMA_VERSIONS
+                    ),
+                );
+                version
+            }
+            None => {
+                issues.error(
+                    Some("schemaVersion".to_string()),
+                    "must be a positive integer",
+                );
+                CURRENT_SCHEMA_VERSION
+            }
+        },
+    }
+}
+
+fn check_metadata(manifest: &Value, issues: &mut Issues) {
+    for field in ["id", "name", "description"] {
+        match manifest.get(field) {
+            Some(Value::String(value)) if !value.trim().is_empty() => {}
+            Some(Value::String(_)) if field == "description" => {}
+            Some(Value::String(_)) => issues.error(Some(field.to_string()), "must not be empty"),
+            Some(_) => issues.error(Some(field.to_string()), "must be a string"),
+            None => issues.error(Some(field.to_string()), "missing"),
+        }
+    }
+    for field in ["default", "hidden"] {
+        if !manifest.get(field).map(Value::is_boolean).unwrap_or(false) {
+            issues.error(Some(field.to_string()), "missing or not a boolean");
+        }
+    }
+
+    if let Some(Value::String(id)) = manifest.get("id") {
+        if id.starts_with('.') || id.contains(['/', '\\', ':']) {
+            issues.error(
+                Some("id".to_string()),
+                "must be a plain directory name (no '/', '\\', ':' or leading '.')",
+            );
+        }
+    }
+
+    let minecraft = manifest.get("minecraft");
+    match minecraft.and_then(|m| m.get("version")) {
+        Some(Value::String(version)) if !version.trim().is_empty() => {}
+        _ => issues.error(Some("minecraft.version".to_string()), "missing or empty"),
+    }
+    if !minecraft
+        .and_then(|m| m.get("recommendedMemory"))
+        .map(Value::is_u64)
+        .unwrap_or(false)
+    {
+        issues.error(
+            Some("minecraft.recommendedMemory".to_string()),
+            "missing or not a positive integer",
+        );
+    }
+
+    if let Some(changelog) = manifest.get("changelog") {
+        if !changelog.is_string() && !changelog.is_null() {
+            issues.error(Some("changelog".to_string()), "must be a string");
+        }
+    }
+
+    match manifest.get("server") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(server)) if deep_link::is_server_address(server) => {}
+        Some(_) => issues.error(Some("server".to_string()), "must be a host or host:port"),
+    }
+}
+
+fn check_loaders(manifest: &Value, issues: &mut Issues) {
+    let loaders = match manifest.get("loaders") {
+        Some(Value::Array(loaders)) => loaders,
+        Some(_) => return issues.error(Some("loaders".to_string()), "must be an array"),
+        None => return issues.error(Some("loaders".to_string()), "missing (use [] for vanilla)"),
+    };
+
+    let mut defaults = 0;
+    for (index, loader) in loaders.iter().enumerate() {
+        let entry = Some(format!("loaders[{}]", index));
+        let loader_type = loader.get("type").and_then(Value::as_str).unwrap_or("");
+        if !LOADER_TYPES.contains(&loader_type.to_ascii_lowercase().as_str()) {
+            issues.error(
+                entry.clone(),
+                format!(
+                    "unsupported type '{}' (expected one of {:?})",
+                    loader_type, LOADER_TYPES
+                ),
+            );
+        }
+
+        let version = loader.get("version").and_then(Value::as_str).unwrap_or("");
+        if version.trim().is_empty() && !loader_type.eq_ignore_ascii_case("vanilla") {
+            issues.error(entry.clone(), "missing version");
+        }
+        if loader.get("default").and_then(Value::as_bool) == Some(true) {
+            defaults += 1;
+        }
+    }
+
+    if defaults > 1 {
+        issues.error(
+            Some("loaders".to_string()),
+            format!(
+                "{} entries are marked as default, expected at most one",
+                defaults
+            ),
+        );
+    } else if loaders.len() > 1 && defaults == 0 {
+        issues.warning(
+            Some("loaders".to_string()),
+            "several loaders listed without \"default\": the first one is used",
+        );
+    }
+}
+
+fn check_files(manifest: &Value, issues: &mut Issues) {
+    let files = match manifest.get("files") {
+        Some(Value::Array(files)) => files,
+        Some(_) => return issues.error(Some("files".to_string()), "must be an array"),
+        None => return issues.error(Some("files".to_string()), "missing"),
+    };
+
+    // Chemins déjà vus (exacts et en minuscules : doublons sous Windows/macOS)
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut seen_lowercase: HashMap<String, usize> = HashMap::new();
+
+    for (index, file) in files.iter().enumerate() {
+        let entry = Some(format!("files[{}]", index));
+
+        match file.get("url").and_then(Value::as_str) {
+            Some(url) => check_url(url, &entry, issues),
+            None => issues.error(entry.clone(), "missing url"),
+        }
+        if let Some(mirrors) = file.get("mirrors") {
+            match mirrors.as_array() {
+                Some(mirrors) => {
+                    for mirror in mirrors {
+                        match mirror.as_str() {
+                            Some(url) => check_url(url, &entry, issues),
+                            None => issues.error(entry.clone(), "mirrors must be strings"),
+                        }
+                    }
+                }
+                None => issues.error(entry.clone(), "mirrors must be an array"),
+            }
+        }
+
+        match file.get("hash").and_then(Value::as_str) {
+            Some(hash) if store::is_sha1(hash) => {}
+            Some(hash) => issues.error(
+                entry.clone(),
+                format!("hash '{}' is not a 40-character hex SHA-1", hash),
+            ),
+            None => issues.error(entry.clone(), "missing hash"),
+        }
+
+        if !file.get("size").map(Value::is_u64).unwrap_or(false) {
+            issues.error(entry.clone(), "missing size or not a positive integer");
+        }
+
+        let path = match file.get("path").and_then(Value::as_str) {
+            Some(path) => path,
+            None => {
+                issues.error(entry.clone(), "missing path");
+                continue;
+            }
+        };
+        if let Err(e) = check_relative_path(path) {
+            issues.error(entry.clone(), e);
+            continue;
+        }
+        check_path_prefix(path, &entry, issues);
+
+        if let Some(first) = seen.insert(path.to_string(), index) {
+            issues.error(
+                entry.clone(),
+                format!("duplicate path '{}' (also files[{}])", path, first),
+            );
+        } else if let Some(first) = seen_lowercase.insert(path.to_lowercase(), index) {
+            issues.warning(
+                entry.clone(),
+                format!(
+                    "path '{}' differs only by case from files[{}] (conflicts on Windows/macOS)",
+                    path, first
+                ),
+            );
+        }
+    }
+}
+
+fn check_url(url: &str, entry: &Option<String>, issues: &mut Issues) {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "https" => {}
+        Ok(_) => issues.error(entry.clone(), format!("url '{}' is not HTTPS", url)),
+        Err(e) => issues.error(entry.clone(), format!("invalid url '{}': {}", url, e)),
+    }
+}
+
+fn check_path_prefix(path: &str, entry: &Option<String>, issues: &mut Issues) {
+    let top_level = match path.split_once('/') {
+        Some((top_level, _)) => top_level,
+        // Fichier à la racine de l'instance (options.txt, servers.dat...)
+        None => return,
+    };
+
+    if !KNOWN_TOP_LEVEL_DIRS.contains(&top_level) {
+        issues.warning(
+            entry.clone(),
+            format!("unexpected top-level directory '{}/'", top_level),
+        );
+    }
+    if path.ends_with(".jar") && top_level != "mods" {
+        issues.warning(entry.clone(), format!("jar '{}' is outside mods/", path));
+    }
+}
+
+/// Compare la taille renvoyée par `HEAD` (Content-Length) au champ `size` de chaque fichier
+async fn check_remote_sizes(manifest: &Value, issues: &mut Issues) {
+    let files = match manifest.get("files").and_then(Value::as_array) {
+        Some(files) => files,
+        None => return,
+    };
+
+    let client = http::client();
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_HEAD_REQUESTS));
+    let mut tasks = JoinSet::new();
+
+    for (index, file) in files.iter().enumerate() {
+        let (Some(url), Some(size)) = (
+            file.get("url").and_then(Value::as_str),
+            file.get("size").and_then(Value::as_u64),
+        ) else {
+            continue;
+        };
+        let url = url.to_string();
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            let result = client
+                .head(&url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            (index, url, size, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        if let Ok(result) = result {
+            results.push(result);
+        }
+    }
+    results.sort_by_key(|(index, ..)| *index);
+
+    for (index, url, size, result) in results {
+        let entry = Some(format!("files[{}]", index));
+        match result {
+            // `content_length()` ne reflète pas l'en-tête pour une réponse à `HEAD`
+            Ok(response) => match response
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+            {
+                Some(length) if length != size => issues.error(
+                    entry,
+                    format!("size is {} but {} serves {} bytes", size, url, length),
+                ),
+                Some(_) => {}
+                None => issues.warning(entry, format!("{} does not report a size", url)),
+            },
+            Err(e) => issues.error(entry, format!("HEAD {} failed: {}", url, e)),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use reqwest::Url;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// Délai maximal de la sonde d'un miroir
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Latence mesurée par origine (`https://host:port`) ; `None` : miroir injoignable
+pub type OriginLatencies = HashMap<String, Option<Duration>>;
+
+/// Origine (`scheme://host:port`) d'une URL
+pub fn origin(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+}
+
+/// URL de `url` sur un miroir global : la base remplace l'origine, le chemin est conservé.
+///
+/// `https://mirror.example.com/miratopia` + `https://host/a/b.jar` -> `https://mirror.example.com/miratopia/a/b.jar`
+pub fn mirror_url(base: &str, url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let base = base.trim_end_matches('/');
+    Url::parse(base).ok()?;
+
+    let mut mirrored = format!("{}{}", base, url.path());
+    if let Some(query) = url.query() {
+        mirrored.push('?');
+        mirrored.push_str(query);
+    }
+    Some(mirrored)
+}
+
+/// URLs candidates d'un fichier, dans l'ordre du manifest :
+/// URL principale, miroirs du fichier, puis miroirs globaux de `launcher.json`.
+pub fn candidate_urls(
+    primary: &str,
+    file_mirrors: &[String],
+    global_bases: &[String],
+) -> Vec<String> {
+    let mut urls = vec![primary.to_string()];
+    urls.extend(file_mirrors.iter().cloned());
+    urls.extend(
+        global_bases
+            .iter()
+            .filter_map(|base| mirror_url(base, primary)),
+    );
+
+    let mut seen = Vec::new();
+    urls.retain(|url| {
+        if seen.contains(url) {
+            false
+        } else {
+            seen.push(url.clone());
+            true
+        }
+    });
+    urls
+}
+
+/// Sonde (requête `HEAD`) une URL par origine et mesure sa latence.
+///
+/// Une réponse HTTP < 500 (même 404/405 : `HEAD` pas toujours supporté) compte comme joignable.
+pub async fn probe_origins<'a, I>(client: &reqwest::Client, urls: I) -> OriginLatencies
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let mut targets: HashMap<String, String> = HashMap::new();
+    for url in urls {
+        if let Some(origin) = origin(url) {
+            targets.entry(origin).or_insert_with(|| url.clone());
+        }
+    }
+
+    let mut tasks = JoinSet::new();
+    for (origin, url) in targets {
+        let client = client.clone();
+        tasks.spawn(async move {
+            let start = Instant::now();
+            let latency = match client.head(&url).timeout(PROBE_TIMEOUT).send().await {
+                Ok(response) if !response.status().is_server_error() => Some(start.elapsed()),
+                Ok(response) => {
+                    tracing::warn!("Mirror {} answered {}", origin, response.status());
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Mirror {} unreachable: {}", origin, e);
+                    None
+                }
+            };
+            (origin, latency)
+        });
+    }
+
+    let mut latencies = OriginLatencies::new();
+    while let Some(result) = tasks.join_next().await {
+        if let Ok((origin, latency)) = result {
+            tracing::debug!("Mirror {} latency: {:?}", origin, latency);
+            latencies.insert(origin, latency);
+        }
+    }
+    latencies
+}
+
+/// Trie les URLs : miroirs joignables du plus rapide au plus lent, puis les autres.
+///
+/// Le tri est stable : à latence inconnue, l'ordre du manifest est conservé.
+pub fn sort_by_latency(urls: &mut [String], latencies: &OriginLatencies) {
+    urls.sort_by_key(|url| {
+        match origin(url).and_then(|origin| latencies.get(&origin).copied()) {
+            Some(Some(latency)) => (0, latency),
+            // Pas sondé : on garde sa place parmi les miroirs joignables
+            None => (1, Duration::ZERO),
+            Some(None) => (2, Duration::ZERO),
+        }
+    });
+}
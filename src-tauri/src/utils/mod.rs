@@ -1 +1,17 @@
+pub mod args;
+pub mod console_history;
+pub mod deep_link;
+pub mod download;
+pub mod http;
+pub mod log4j;
+pub mod manifest;
+pub mod mirrors;
+pub mod paths;
+pub mod progress;
+pub mod redact;
+pub mod running;
+pub mod sessions;
+pub mod signature;
+pub mod store;
+pub mod sync;
 pub mod vault;
@@ -0,0 +1,73 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Vérifie qu'un chemin du manifest est relatif et reste dans l'instance :
+/// pas de chemin absolu, de préfixe de lecteur (`C:`), de `\` ni de `..`.
+pub fn check_relative_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("path is empty".to_string());
+    }
+    if path.contains('\0') {
+        return Err(format!("path '{}' contains a NUL byte", path));
+    }
+    if path.starts_with('/') || path.starts_with('\\') {
+        return Err(format!("path '{}' is absolute", path));
+    }
+    if path.contains(':') {
+        return Err(format!("path '{}' contains a drive prefix or ':'", path));
+    }
+    if path.contains('\\') {
+        return Err(format!("path '{}' uses '\\' instead of '/'", path));
+    }
+    if path.split('/').any(|component| component == "..") {
+        return Err(format!("path '{}' contains '..'", path));
+    }
+    // Filet de sécurité : l'interprétation du système doit donner uniquement des noms simples
+    let is_plain = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !is_plain {
+        return Err(format!("path '{}' escapes the instance directory", path));
+    }
+    Ok(())
+}
+
+/// Vérifie que `path` (dans `base`) ne sort pas de `base` via un lien symbolique.
+///
+/// Le plus profond élément existant du chemin est résolu : un dossier ou fichier lié vers
+/// l'extérieur de `base` est refusé, comme un lien cassé qu'on ne peut pas résoudre.
+pub fn ensure_within(base: &Path, path: &Path) -> Result<(), String> {
+    let Ok(base) = base.canonicalize() else {
+        // Dossier pas encore créé : aucun lien ne peut y exister
+        return Ok(());
+    };
+
+    for ancestor in path.ancestors() {
+        if std::fs::symlink_metadata(ancestor).is_err() {
+            continue;
+        }
+        let resolved = ancestor.canonicalize().map_err(|e| {
+            format!(
+                "'{}' cannot be resolved (broken symlink?): {}",
+                ancestor.display(),
+                e
+            )
+        })?;
+        if !resolved.starts_with(&base) {
+            return Err(format!(
+                "'{}' resolves outside of '{}' (symlink escape)",
+                path.display(),
+                base.display()
+            ));
+        }
+        return Ok(());
+    }
+    Ok(())
+}
+
+/// Chemin sur le disque d'un fichier du manifest, garanti à l'intérieur de `base`
+pub fn resolve_in(base: &Path, relative: &str) -> Result<PathBuf, String> {
+    check_relative_path(relative).map_err(|e| format!("Unsafe path '{}': {}", relative, e))?;
+    let path = base.join(relative);
+    ensure_within(base, &path).map_err(|e| format!("Unsafe path '{}': {}", relative, e))?;
+    Ok(path)
+}
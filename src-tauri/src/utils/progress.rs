@@ -0,0 +1,256 @@
+use crate::types::{DownloadPhase, LaunchProgressPayload, PhaseProgress};
+use std::time::{Duration, Instant};
+
+/// Ordre et poids (en %) des phases d'un lancement.
+///
+/// `Modpack` correspond à la synchronisation des fichiers du manifest, faite avant lighty.
+/// Lighty compte les librairies et les assets dans les octets de `InstallProgress` : ils
+/// font partie de `Game`, sans phase propre.
+const PHASE_PLAN: &[(DownloadPhase, u32)] = &[
+    (DownloadPhase::Modpack, 20),
+    (DownloadPhase::Loader, 5),
+    (DownloadPhase::Java, 15),
+    (DownloadPhase::Extracting, 10),
+    (DownloadPhase::Game, 50),
+];
+
+/// Fenêtre de mesure du débit
+const SPEED_WINDOW: Duration = Duration::from_secs(1);
+
+/// Lissage du débit (moyenne mobile exponentielle)
+const SPEED_SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone)]
+struct PhaseState {
+    phase: DownloadPhase,
+    weight: u32,
+    current: u64,
+    total: u64,
+    started: bool,
+    completed: bool,
+}
+
+impl PhaseState {
+    fn fraction(&self) -> f64 {
+        if self.completed {
+            1.0
+        } else if self.total > 0 {
+            (self.current as f64 / self.total as f64).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Suivi de la progression globale d'un lancement.
+///
+/// Les phases sont pondérées selon `PHASE_PLAN` : démarrer une phase marque les précédentes
+/// comme terminées (une phase sautée, ex. Java déjà installé, compte comme faite),
+/// et une phase terminée ne recule plus. Le pourcentage global est donc monotone.
+#[derive(Debug, Clone)]
+pub struct ProgressTracker {
+    phases: Vec<PhaseState>,
+    current_phase: DownloadPhase,
+    finished: bool,
+    bytes_per_second: f64,
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+impl Default for ProgressTracker {
+    fn default() -> Self {
+        Self {
+            phases: PHASE_PLAN
+                .iter()
+                .map(|(phase, weight)| PhaseState {
+                    phase: *phase,
+                    weight: *weight,
+                    current: 0,
+                    total: 0,
+                    started: false,
+                    completed: false,
+                })
+                .collect(),
+            current_phase: DownloadPhase::Idle,
+            finished: false,
+            bytes_per_second: 0.0,
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+}
+
+impl ProgressTracker {
+    /// Démarre (ou redémarre) une phase avec son total connu.
+    ///
+    /// Si le lancement précédent est terminé, un nouveau suivi commence.
+    pub fn start_phase(&mut self, phase: DownloadPhase, total: u64) {
+        if self.finished {
+            *self = Self::default();
+        }
+
+        let index = match self.index_of(phase) {
+            Some(index) => index,
+            None => return,
+        };
+
+        // Une phase déjà terminée (ex. extraction des natives après celle de Java) ne recule pas
+        if self.phases[index].completed {
+            self.current_phase = phase;
+            return;
+        }
+
+        for previous in &mut self.phases[..index] {
+            if !previous.completed {
+                previous.completed = true;
+            }
+        }
+
+        let state = &mut self.phases[index];
+        state.started = true;
+        state.current = 0;
+        state.total = total;
+        self.current_phase = phase;
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+    }
+
+    /// Ajoute des octets téléchargés à une phase (mesure aussi le débit)
+    pub fn add_bytes(&mut self, phase: DownloadPhase, bytes: u64) {
+        if let Some(state) = self.phase_mut(phase) {
+            state.current = state.current.saturating_add(bytes);
+        }
+        self.record_bytes(bytes);
+    }
+
+    /// Fixe l'avancement d'une phase mesurée en unités (fichiers extraits...)
+    pub fn set_progress(&mut self, phase: DownloadPhase, current: u64, total: u64) {
+        if let Some(state) = self.phase_mut(phase) {
+            state.started = true;
+            state.current = current;
+            state.total = total;
+        }
+    }
+
+    pub fn complete_phase(&mut self, phase: DownloadPhase) {
+        if let Some(state) = self.phase_mut(phase) {
+            state.started = true;
+            state.completed = true;
+            if state.total > 0 {
+                state.current = state.total;
+            }
+        }
+    }
+
+    /// Abandonne le suivi en cours (lancement annulé ou en échec)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Termine le suivi : toutes les phases sont considérées faites
+    pub fn finish(&mut self) {
+        for state in &mut self.phases {
+            state.completed = true;
+        }
+        self.finished = true;
+        self.bytes_per_second = 0.0;
+    }
+
+    pub fn overall_percentage(&self) -> u8 {
+        let total_weight: u32 = self.phases.iter().map(|state| state.weight).sum();
+        if total_weight == 0 {
+            return 0;
+        }
+
+        let done: f64 = self
+            .phases
+            .iter()
+            .map(|state| state.weight as f64 * state.fraction())
+            .sum();
+        ((done / total_weight as f64) * 100.0).min(100.0) as u8
+    }
+
+    /// Construit le payload `lighty://launch-progress`
+    pub fn snapshot(&self, instance_name: &str, message: String) -> LaunchProgressPayload {
+        let phases = self
+            .phases
+            .iter()
+            .filter(|state| state.weight > 0 || state.started)
+            .map(|state| PhaseProgress {
+                phase: state.phase,
+                current: state.current,
+                total: state.total,
+                percentage: (state.fraction() * 100.0) as u8,
+                weight: state.weight,
+                completed: state.completed,
+            })
+            .collect();
+
+        let completed_phases = self
+            .phases
+            .iter()
+            .filter(|state| state.completed)
+            .map(|state| state.phase)
+            .collect();
+
+        LaunchProgressPayload {
+            instance_name: instance_name.to_string(),
+            current_phase: self.current_phase,
+            overall_percentage: self.overall_percentage(),
+            phases,
+            completed_phases,
+            bytes_per_second: self.bytes_per_second as u64,
+            eta_seconds: self.eta_seconds(),
+            message,
+        }
+    }
+
+    /// Temps restant estimé pour la phase de téléchargement en cours
+    fn eta_seconds(&self) -> Option<u64> {
+        // Seules ces phases sont mesurées en octets
+        let measured_in_bytes = matches!(
+            self.current_phase,
+            DownloadPhase::Modpack | DownloadPhase::Java | DownloadPhase::Game
+        );
+        if !measured_in_bytes || self.bytes_per_second < 1.0 {
+            return None;
+        }
+
+        let state = self
+            .phases
+            .iter()
+            .find(|state| state.phase == self.current_phase)?;
+        if state.completed || state.total == 0 {
+            return None;
+        }
+
+        let remaining = state.total.saturating_sub(state.current);
+        Some((remaining as f64 / self.bytes_per_second).ceil() as u64)
+    }
+
+    fn record_bytes(&mut self, bytes: u64) {
+        self.window_bytes = self.window_bytes.saturating_add(bytes);
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed < SPEED_WINDOW {
+            return;
+        }
+
+        let instant = self.window_bytes as f64 / elapsed.as_secs_f64();
+        self.bytes_per_second = if self.bytes_per_second == 0.0 {
+            instant
+        } else {
+            self.bytes_per_second * (1.0 - SPEED_SMOOTHING) + instant * SPEED_SMOOTHING
+        };
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+    }
+
+    fn index_of(&self, phase: DownloadPhase) -> Option<usize> {
+        self.phases.iter().position(|state| state.phase == phase)
+    }
+
+    fn phase_mut(&mut self, phase: DownloadPhase) -> Option<&mut PhaseState> {
+        self.phases.iter_mut().find(|state| state.phase == phase)
+    }
+}
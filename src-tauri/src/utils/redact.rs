@@ -0,0 +1,149 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::io::Write;
+use std::sync::RwLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Texte de remplacement des valeurs masquées
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Longueur minimale d'un secret enregistré (évite de masquer des valeurs triviales)
+const MIN_SECRET_LEN: usize = 8;
+
+/// Secrets connus (tokens des comptes chargés), masqués partout où ils apparaissent
+static SECRETS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Dossier personnel de l'utilisateur, remplacé par `~`
+static HOME_DIR: Lazy<Option<String>> = Lazy::new(|| {
+    dirs::home_dir()
+        .and_then(|home| home.to_str().map(str::to_string))
+        .filter(|home| home.len() > 1)
+});
+
+/// Arguments de lancement du jeu : `--accessToken <token>`
+static ARGUMENT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(--(?:accessToken|session|sessionId|clientId|xuid)[\s=]+)(\S+)").unwrap()
+});
+
+/// Paires clé/valeur (JSON, query string, Debug) : `access_token: "..."`, `refreshToken=...`
+static KEY_VALUE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)("?(?:access_?token|refresh_?token|session_?id|id_?token|client_?secret)"?\s*[:=]\s*(?:Some\()?"?)([^"\s,)}&]+)"#,
+    )
+    .unwrap()
+});
+
+/// Session Minecraft liée à l'UUID du joueur : `token:<accessToken>:<uuid>`
+static SESSION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"token:[^:\s]+:([0-9a-fA-F]{8}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{12})")
+        .unwrap()
+});
+
+/// JWT (les access tokens Minecraft / Xbox en sont)
+static JWT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}").unwrap()
+});
+
+/// Enregistre un secret (access/refresh token) à masquer dans les logs et la console
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+/// Masque les tokens, sessions et le dossier personnel dans un texte
+pub fn redact(input: &str) -> String {
+    let mut output = input.to_string();
+
+    for secret in SECRETS.read().unwrap().iter() {
+        if output.contains(secret.as_str()) {
+            output = output.replace(secret.as_str(), REDACTED);
+        }
+    }
+
+    output = SESSION_PATTERN
+        .replace_all(&output, |caps: &Captures| {
+            format!("token:{}:{}", REDACTED, &caps[1])
+        })
+        .into_owned();
+    output = ARGUMENT_PATTERN
+        .replace_all(&output, |caps: &Captures| {
+            format!("{}{}", &caps[1], REDACTED)
+        })
+        .into_owned();
+    output = KEY_VALUE_PATTERN
+        .replace_all(&output, |caps: &Captures| {
+            if &caps[2] == REDACTED {
+                caps[0].to_string()
+            } else {
+                format!("{}{}", &caps[1], REDACTED)
+            }
+        })
+        .into_owned();
+    output = JWT_PATTERN.replace_all(&output, REDACTED).into_owned();
+
+    if let Some(home) = HOME_DIR.as_deref() {
+        // Chemin tel quel et échappé (JSON / Debug sous Windows)
+        let escaped = home.replace('\\', "\\\\");
+        if escaped != home {
+            output = output.replace(&escaped, "~");
+        }
+        output = output.replace(home, "~");
+    }
+
+    output
+}
+
+/// Version octets de `redact` (fichiers, sortie des writers)
+pub fn redact_bytes(input: &[u8]) -> Vec<u8> {
+    redact(&String::from_utf8_lossy(input)).into_bytes()
+}
+
+/// `MakeWriter` qui masque les secrets avant écriture (sortie standard et fichier de log)
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, M> MakeWriter<'a> for RedactingMakeWriter<M>
+where
+    M: MakeWriter<'a>,
+{
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+        }
+    }
+}
+
+/// Writer produit par `RedactingMakeWriter`.
+///
+/// Le layer `fmt` formate chaque événement complet avant de l'écrire en une fois,
+/// le masquage peut donc se faire sur chaque buffer reçu.
+pub struct RedactingWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write_all(&redact_bytes(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,70 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use sysinfo::{Pid, ProcessExt, Signal, System, SystemExt};
+
+/// Instances dont le jeu tourne, indexées par nom d'instance (PID du jeu)
+static RUNNING_INSTANCES: Lazy<Mutex<HashMap<String, u32>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Appelé sur `InstanceLaunched`
+pub fn mark_running(instance_name: &str, pid: u32) {
+    RUNNING_INSTANCES
+        .lock()
+        .unwrap()
+        .insert(instance_name.to_string(), pid);
+}
+
+/// Appelé sur `InstanceExited`
+pub fn mark_exited(instance_name: &str) {
+    RUNNING_INSTANCES.lock().unwrap().remove(instance_name);
+}
+
+pub fn is_running(instance_name: &str) -> bool {
+    RUNNING_INSTANCES
+        .lock()
+        .unwrap()
+        .contains_key(instance_name)
+}
+
+/// Instances en cours d'exécution (nom, PID), triées par nom
+pub fn running_instances() -> Vec<(String, u32)> {
+    let mut instances: Vec<(String, u32)> = RUNNING_INSTANCES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, pid)| (name.clone(), *pid))
+        .collect();
+    instances.sort();
+    instances
+}
+
+/// Termine le processus du jeu d'une instance ; `InstanceExited` met ensuite le registre à jour
+pub fn stop_instance(instance_name: &str) -> Result<(), String> {
+    let pid = RUNNING_INSTANCES
+        .lock()
+        .unwrap()
+        .get(instance_name)
+        .copied()
+        .ok_or_else(|| format!("Instance '{}' is not running", instance_name))?;
+
+    let pid = pid as Pid;
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        // Processus déjà terminé
+        mark_exited(instance_name);
+        return Ok(());
+    }
+    let process = system
+        .get_process(pid)
+        .ok_or_else(|| format!("Process {} not found", pid))?;
+    if !process.kill(Signal::Kill) {
+        return Err(format!(
+            "Failed to stop instance '{}' (PID {})",
+            instance_name, pid
+        ));
+    }
+    super::sessions::mark_stopped(instance_name);
+    tracing::info!("Stopped instance '{}' (PID {})", instance_name, pid);
+    Ok(())
+}
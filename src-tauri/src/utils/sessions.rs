@@ -0,0 +1,189 @@
+use crate::types::{ModpackPlayStats, PlaySession};
+use lighty_launcher::core::AppState;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Historique des sessions dans le dossier des instances ; un fichier, donc conservé par
+/// `delete_all_modpacks`
+const SESSIONS_FILE: &str = ".sessions.json";
+
+/// Sessions conservées, les plus anciennes sont supprimées au-delà
+const MAX_SESSIONS: usize = 1000;
+
+/// Modpack et compte d'un lancement, connus avant le démarrage du jeu
+#[derive(Debug, Clone, Default)]
+struct LaunchInfo {
+    modpack_name: Option<String>,
+    account: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveSession {
+    modpack_name: Option<String>,
+    account: Option<String>,
+    started_at: u64,
+    /// Arrêt demandé par le joueur : la sortie n'est pas un crash
+    stopped: bool,
+}
+
+/// Prochain lancement, par nom d'instance (renseigné avant le démarrage du jeu)
+static PENDING_LAUNCHES: Lazy<Mutex<HashMap<String, LaunchInfo>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Sessions en cours, par nom d'instance
+static ACTIVE_SESSIONS: Lazy<Mutex<HashMap<String, ActiveSession>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Sérialise les écritures de l'historique
+static FILE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn sessions_path() -> PathBuf {
+    AppState::get_project_dirs().data_dir().join(SESSIONS_FILE)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Modpack (`launcher.json`) et compte du prochain lancement de `instance_name`
+pub fn set_launch_info(instance_name: &str, modpack_name: &str, account: &str) {
+    PENDING_LAUNCHES.lock().unwrap().insert(
+        instance_name.to_string(),
+        LaunchInfo {
+            modpack_name: Some(modpack_name.to_string()),
+            account: Some(account.to_string()),
+        },
+    );
+}
+
+/// Appelé sur `InstanceLaunched`
+pub fn start(instance_name: &str) {
+    let launch = PENDING_LAUNCHES
+        .lock()
+        .unwrap()
+        .remove(instance_name)
+        .unwrap_or_default();
+    ACTIVE_SESSIONS.lock().unwrap().insert(
+        instance_name.to_string(),
+        ActiveSession {
+            modpack_name: launch.modpack_name,
+            account: launch.account,
+            started_at: now(),
+            stopped: false,
+        },
+    );
+}
+
+/// Arrêt du jeu demandé par le joueur (tray, bouton stop)
+pub fn mark_stopped(instance_name: &str) {
+    if let Some(session) = ACTIVE_SESSIONS.lock().unwrap().get_mut(instance_name) {
+        session.stopped = true;
+    }
+}
+
+/// Appelé sur `InstanceExited` : enregistre la session dans l'historique
+pub fn finish(instance_name: &str, exit_code: Option<i32>) {
+    let Some(active) = ACTIVE_SESSIONS.lock().unwrap().remove(instance_name) else {
+        tracing::debug!("No active session for '{}'", instance_name);
+        return;
+    };
+
+    let session = PlaySession {
+        modpack: instance_name.to_string(),
+        modpack_name: active.modpack_name,
+        account: active.account,
+        started_at: active.started_at,
+        ended_at: now(),
+        exit_code,
+        crashed: !active.stopped && exit_code != Some(0),
+    };
+    if let Err(e) = append(session) {
+        tracing::warn!("Failed to record session of '{}': {}", instance_name, e);
+    }
+}
+
+fn append(session: PlaySession) -> Result<(), String> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    let path = sessions_path();
+    let mut sessions = match load_sessions(&path) {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            // Historique illisible : mis de côté plutôt qu'écrasé
+            let backup = path.with_extension("json.bak");
+            std::fs::rename(&path, &backup)
+                .map_err(|rename_err| format!("{} (backup failed: {})", e, rename_err))?;
+            tracing::warn!("{}, previous history moved to {:?}", e, backup);
+            Vec::new()
+        }
+    };
+    sessions.push(session);
+    if sessions.len() > MAX_SESSIONS {
+        sessions.drain(..sessions.len() - MAX_SESSIONS);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_vec(&sessions).map_err(|e| e.to_string())?;
+    // Fichier temporaire puis renommage : un arrêt brutal ne laisse pas d'historique tronqué
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+}
+
+/// Historique sur le disque : vide si absent, erreur s'il est illisible
+fn load_sessions(path: &Path) -> Result<Vec<PlaySession>, String> {
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+    serde_json::from_slice(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+}
+
+/// Historique complet, du plus ancien au plus récent (vide si absent ou illisible)
+pub fn read_sessions() -> Vec<PlaySession> {
+    load_sessions(&sessions_path()).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring play history: {}", e);
+        Vec::new()
+    })
+}
+
+/// Sessions les plus récentes d'abord
+pub fn recent_sessions(limit: usize) -> Vec<PlaySession> {
+    read_sessions().into_iter().rev().take(limit).collect()
+}
+
+/// Temps de jeu et dernière session par modpack, le plus récemment joué d'abord
+pub fn play_stats() -> Vec<ModpackPlayStats> {
+    let mut stats: HashMap<String, ModpackPlayStats> = HashMap::new();
+    for session in read_sessions() {
+        let entry = stats
+            .entry(session.modpack.clone())
+            .or_insert_with(|| ModpackPlayStats {
+                modpack: session.modpack.clone(),
+                modpack_name: None,
+                total_seconds: 0,
+                session_count: 0,
+                last_played: 0,
+            });
+        entry.total_seconds += session.ended_at.saturating_sub(session.started_at);
+        entry.session_count += 1;
+        // Sessions dans l'ordre : le nom le plus récent l'emporte
+        if session.modpack_name.is_some() {
+            entry.modpack_name = session.modpack_name;
+        }
+        entry.last_played = entry.last_played.max(session.ended_at);
+    }
+
+    let mut stats: Vec<ModpackPlayStats> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.last_played.cmp(&a.last_played));
+    stats
+}
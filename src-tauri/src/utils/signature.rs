@@ -0,0 +1,74 @@
+use crate::utils::http;
+use minisign_verify::{PublicKey, Signature};
+
+/// Extension de la signature détachée publiée à côté de chaque manifest
+/// (`launcher.json.minisig`, `modpack.json.minisig`).
+const SIGNATURE_EXTENSION: &str = ".minisig";
+
+/// Clés publiques minisign acceptées pour les manifests (`launcher.json`, `modpack.json`).
+///
+/// Rotation : ajouter la nouvelle clé ici et publier une version du launcher, re-signer les
+/// manifests avec la nouvelle clé, puis retirer l'ancienne dans une version suivante.
+/// Signature : `minisign -S -m modpack.json` ou `tauri signer sign modpack.json`.
+const TRUSTED_KEYS: &[(&str, &str)] = &[
+    // Clé du projet, également utilisée par l'updater (`tauri.conf.json`)
+    (
+        "92274E1BC116FECD",
+        "RWTN/hbBG04nkjGmdAmuGN6SUfdpUlab4KS7YvER3SqIhtVZWz40aP1r",
+    ),
+];
+
+/// Vérifie `content` avec sa signature minisign détachée contre les clés embarquées.
+///
+/// Retourne l'identifiant de la clé qui a validé la signature.
+pub fn verify(content: &[u8], signature: &str) -> Result<&'static str, String> {
+    let signature =
+        Signature::decode(signature).map_err(|e| format!("Malformed signature: {}", e))?;
+
+    let mut errors = Vec::new();
+    for (key_id, key) in TRUSTED_KEYS {
+        let public_key = PublicKey::from_base64(key)
+            .map_err(|e| format!("Invalid embedded key {}: {}", key_id, e))?;
+        // `allow_legacy` : `tauri signer` produit des signatures non pré-hachées
+        match public_key.verify(content, &signature, true) {
+            Ok(()) => return Ok(key_id),
+            Err(e) => errors.push(format!("{}: {}", key_id, e)),
+        }
+    }
+    Err(format!(
+        "Signature does not match any trusted key ({})",
+        errors.join("; ")
+    ))
+}
+
+/// Télécharge un manifest et sa signature (`<url>.minisig`), puis vérifie la signature.
+///
+/// Un manifest non signé ou mal signé est refusé : son contenu n'est jamais retourné.
+pub async fn fetch_signed(url: &str) -> Result<Vec<u8>, String> {
+    let content = http::get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?;
+
+    let signature_url = format!("{}{}", url, SIGNATURE_EXTENSION);
+    let signature = http::get(&signature_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Missing signature for {} ({}): {}", url, signature_url, e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", signature_url, e))?;
+
+    let key_id = verify(&content, &signature).map_err(|e| {
+        let msg = format!("Refusing unsigned or tampered manifest {}: {}", url, e);
+        tracing::error!(%msg);
+        msg
+    })?;
+    tracing::debug!("Manifest {} signed by key {}", url, key_id);
+    Ok(content.to_vec())
+}
@@ -0,0 +1,242 @@
+use crate::utils::download;
+use crate::utils::paths;
+use once_cell::sync::Lazy;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Dossier du store partagé dans le dossier de données du launcher
+const STORE_DIR: &str = ".store";
+
+/// Index des références : SHA-1 -> instances qui utilisent l'objet
+const REFS_FILE: &str = "refs.json";
+
+/// Extensions partagées entre instances.
+///
+/// Seules les archives que le jeu ne réécrit pas sont liées : un fichier de config lié en dur
+/// et modifié en jeu changerait aussi celui des autres instances.
+const SHAREABLE_EXTENSIONS: &[&str] = &["jar", "zip"];
+
+/// Âge à partir duquel un objet sans référence est supprimé. Une synchronisation ne
+/// référence ses objets qu'à la fin : ceux d'une synchronisation en cours sont récents.
+const ORPHAN_GRACE: Duration = Duration::from_secs(24 * 60 * 60);
+
+type Refs = HashMap<String, BTreeSet<String>>;
+
+/// Sérialise les lectures/écritures de `refs.json`
+static REFS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Un verrou par objet : deux instances qui synchronisent le même fichier ne le téléchargent qu'une fois
+static OBJECT_LOCKS: Lazy<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn store_dir() -> PathBuf {
+    lighty_launcher::core::AppState::get_project_dirs()
+        .data_dir()
+        .join(STORE_DIR)
+}
+
+/// SHA-1 hexadécimal (40 caractères) : seule forme acceptée comme nom d'objet
+pub fn is_sha1(hash: &str) -> bool {
+    hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Chemin d'un objet : `.store/objects/ab/abcdef...`
+///
+/// Un hash qui n'est pas un SHA-1 (`../..`) sortirait du store : il est refusé, même si
+/// `fetch_modpack` l'a déjà vérifié.
+pub fn object_path(sha1: &str) -> Result<PathBuf, String> {
+    if !is_sha1(sha1) {
+        return Err(format!("Invalid store object hash '{}'", sha1));
+    }
+    let sha1 = sha1.to_ascii_lowercase();
+    Ok(store_dir().join("objects").join(&sha1[..2]).join(sha1))
+}
+
+/// Le fichier (chemin relatif du manifest) peut-il être partagé via le store ?
+pub fn is_shareable(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            SHAREABLE_EXTENSIONS
+                .iter()
+                .any(|shareable| ext.eq_ignore_ascii_case(shareable))
+        })
+        .unwrap_or(false)
+}
+
+/// Verrouille un objet le temps de le vérifier, le télécharger et le placer
+pub async fn lock_object(sha1: &str) -> tokio::sync::OwnedMutexGuard<()> {
+    let lock = OBJECT_LOCKS
+        .lock()
+        .unwrap()
+        .entry(sha1.to_ascii_lowercase())
+        .or_default()
+        .clone();
+    lock.lock_owned().await
+}
+
+/// Verrou d'un objet s'il est libre : un objet en cours de vérification ou de placement par
+/// une synchronisation n'est pas supprimé sous elle
+fn try_lock_object(sha1: &str) -> Option<tokio::sync::OwnedMutexGuard<()>> {
+    let lock = OBJECT_LOCKS
+        .lock()
+        .unwrap()
+        .entry(sha1.to_ascii_lowercase())
+        .or_default()
+        .clone();
+    lock.try_lock_owned().ok()
+}
+
+/// L'objet existe-t-il avec le bon contenu ? Un objet corrompu est supprimé.
+pub async fn is_valid_object(object: &Path, sha1: &str) -> bool {
+    if !object.is_file() {
+        return false;
+    }
+
+    match download::file_sha1(object).await {
+        Ok(actual) if actual.eq_ignore_ascii_case(sha1) => true,
+        _ => {
+            tracing::warn!("Corrupted store object {:?}, removing it", object);
+            let _ = tokio::fs::remove_file(object).await;
+            false
+        }
+    }
+}
+
+/// Place un objet dans une instance : lien dur, ou copie si le lien est impossible
+/// (autre volume, système de fichiers sans liens durs).
+///
+/// `dest` doit rester dans `instance_dir`, liens symboliques résolus.
+pub fn place(object: &Path, instance_dir: &Path, dest: &Path) -> Result<(), String> {
+    paths::ensure_within(instance_dir, dest)?;
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+    if dest.exists() {
+        std::fs::remove_file(dest)
+            .map_err(|e| format!("Failed to replace '{}': {}", dest.display(), e))?;
+    }
+
+    if let Err(e) = std::fs::hard_link(object, dest) {
+        tracing::debug!("Hard link to {:?} failed ({}), copying", dest, e);
+        std::fs::copy(object, dest)
+            .map_err(|e| format!("Failed to copy to '{}': {}", dest.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Remplace les objets référencés par une instance, puis supprime ceux qui ne servent plus
+pub fn set_instance_refs(instance_id: &str, sha1s: impl IntoIterator<Item = String>) {
+    let sha1s: BTreeSet<String> = sha1s
+        .into_iter()
+        .map(|sha1| sha1.to_ascii_lowercase())
+        .collect();
+
+    update_refs(|refs| {
+        for instances in refs.values_mut() {
+            instances.remove(instance_id);
+        }
+        for sha1 in sha1s {
+            refs.entry(sha1)
+                .or_default()
+                .insert(instance_id.to_string());
+        }
+    });
+}
+
+/// Libère toutes les références d'une instance supprimée (et les objets devenus inutiles)
+pub fn release_instance(instance_id: &str) {
+    update_refs(|refs| {
+        for instances in refs.values_mut() {
+            instances.remove(instance_id);
+        }
+    });
+}
+
+/// Applique une modification à l'index, puis supprime les objets qui n'ont plus de référence
+fn update_refs<F: FnOnce(&mut Refs)>(update: F) {
+    let _guard = REFS_LOCK.lock().unwrap();
+    let path = store_dir().join(REFS_FILE);
+
+    let mut refs: Refs = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    update(&mut refs);
+
+    let mut removed = 0;
+    refs.retain(|sha1, instances| {
+        if !instances.is_empty() {
+            return true;
+        }
+        // Index modifié à la main : on oublie l'entrée sans toucher au disque
+        let Ok(object) = object_path(sha1) else {
+            return false;
+        };
+        // Objet utilisé par une synchronisation : entrée gardée, supprimé au prochain passage
+        let Some(_lock) = try_lock_object(sha1) else {
+            return true;
+        };
+        if object.exists() && std::fs::remove_file(&object).is_ok() {
+            removed += 1;
+        }
+        false
+    });
+    removed += sweep_orphans(&refs);
+    if removed > 0 {
+        tracing::info!("Store garbage collection removed {} object(s)", removed);
+    }
+
+    let result = std::fs::create_dir_all(store_dir()).and_then(|_| {
+        let content = serde_json::to_string_pretty(&refs).map_err(std::io::Error::other)?;
+        std::fs::write(&path, content)
+    });
+    if let Err(e) = result {
+        tracing::error!("Failed to write store index {:?}: {}", path, e);
+    }
+}
+
+/// Supprime les objets (et téléchargements partiels) absents de l'index depuis plus de
+/// `ORPHAN_GRACE` : ceux d'une synchronisation annulée ou en échec ne sont jamais référencés
+fn sweep_orphans(refs: &Refs) -> usize {
+    let Ok(prefixes) = std::fs::read_dir(store_dir().join("objects")) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    let objects = prefixes
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| std::fs::read_dir(entry.path()).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok());
+    for object in objects {
+        let path = object.path();
+        let sha1 = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if refs.contains_key(&sha1) || !object.file_type().is_ok_and(|kind| kind.is_file()) {
+            continue;
+        }
+        let expired = object
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > ORPHAN_GRACE);
+        if !expired {
+            continue;
+        }
+        let Some(_lock) = try_lock_object(&sha1) else {
+            continue;
+        };
+        if std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
@@ -0,0 +1,277 @@
+use crate::utils::download::{self, DownloadConfig, DownloadControl};
+use crate::utils::http;
+use crate::utils::mirrors;
+use crate::utils::paths;
+use crate::utils::store;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+/// Fichier du manifest à installer dans l'instance
+#[derive(Debug, Clone)]
+pub struct SyncFile {
+    /// URLs candidates (principale puis miroirs), voir `mirrors::candidate_urls`
+    pub urls: Vec<String>,
+    /// Chemin relatif au dossier de l'instance (`mods/foo.jar`, `config/bar.toml`)
+    pub path: String,
+    pub sha1: String,
+    pub size: u64,
+}
+
+/// Progression de la synchronisation, relayée au frontend par `events::spawn_sync_listener`
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    Started {
+        total_bytes: u64,
+        total_files: usize,
+    },
+    Progress {
+        bytes: u64,
+    },
+    Completed {
+        downloaded_files: usize,
+    },
+}
+
+/// Fichiers à (re)télécharger pour mettre une instance à jour, calculés par `plan_sync`
+pub struct SyncPlan {
+    instance_dir: PathBuf,
+    pending: Vec<(SyncFile, PathBuf)>,
+    /// Fichiers du manifest précédent retirés du nouveau (mod supprimé ou renommé)
+    removed: Vec<PathBuf>,
+    /// Objets du store utilisés par le manifest complet (références de l'instance)
+    shared_objects: Vec<String>,
+}
+
+impl SyncPlan {
+    /// L'instance est-elle déjà à jour ?
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty() && self.removed.is_empty()
+    }
+
+    /// Chemins relatifs des fichiers à (re)télécharger
+    pub fn pending_paths(&self) -> impl Iterator<Item = &str> {
+        self.pending.iter().map(|(file, _)| file.path.as_str())
+    }
+}
+
+/// Compare les fichiers du manifest à ceux de l'instance.
+///
+/// Les fichiers déjà présents avec le bon SHA-1 sont conservés, ainsi que les fichiers
+/// `ignored` (modifiables par le joueur) s'ils existent déjà. Les fichiers de `previous`
+/// (manifest installé) absents du nouveau manifest sont à supprimer, sauf s'ils sont `ignored`.
+///
+/// Un chemin qui sortirait de l'instance (absolu, `..`, lien symbolique) fait échouer le plan.
+pub async fn plan_sync(
+    instance_dir: &Path,
+    files: Vec<SyncFile>,
+    ignored: &[String],
+    previous: &[String],
+) -> Result<SyncPlan, String> {
+    let mut removed = Vec::new();
+    for path in previous {
+        let still_managed = files.iter().any(|file| &file.path == path);
+        if still_managed || ignored.contains(path) {
+            continue;
+        }
+        // Manifest installé modifié à la main : un chemin hors de l'instance est ignoré
+        match paths::resolve_in(instance_dir, path) {
+            Ok(dest) if dest.is_file() => removed.push(dest),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Not removing '{}': {}", path, e),
+        }
+    }
+
+    let shared_objects: Vec<String> = files
+        .iter()
+        .filter(|file| store::is_shareable(&file.path))
+        .map(|file| file.sha1.clone())
+        .collect();
+
+    let mut pending = Vec::new();
+    for file in files {
+        let dest = paths::resolve_in(instance_dir, &file.path)?;
+        if dest.is_file() {
+            if ignored.iter().any(|path| path == &file.path) {
+                continue;
+            }
+            if is_up_to_date(&dest, &file).await {
+                continue;
+            }
+        }
+        pending.push((file, dest));
+    }
+
+    Ok(SyncPlan {
+        instance_dir: instance_dir.to_path_buf(),
+        pending,
+        removed,
+        shared_objects,
+    })
+}
+
+/// Applique un `SyncPlan` : les fichiers sont téléchargés via `download::download_file`
+/// (reprise, pause et annulation) ; les archives partageables passent par le store commun
+/// (`store`) et sont liées dans l'instance. Les fichiers retirés du manifest sont supprimés
+/// une fois les téléchargements réussis, et leurs objets libérés avec les références.
+pub async fn apply_sync(
+    plan: SyncPlan,
+    control: &DownloadControl,
+    events: mpsc::UnboundedSender<SyncEvent>,
+) -> Result<(), String> {
+    let SyncPlan {
+        instance_dir,
+        mut pending,
+        removed,
+        shared_objects,
+    } = plan;
+
+    let total_bytes = pending.iter().map(|(file, _)| file.size).sum();
+    let _ = events.send(SyncEvent::Started {
+        total_bytes,
+        total_files: pending.len(),
+    });
+
+    // Réglages lus à chaque synchronisation : pas besoin de redémarrer après un changement
+    let config = Arc::new(download::download_config());
+    let client = http::client();
+
+    // Avec des miroirs, on sonde chaque hôte une fois pour essayer le plus rapide en premier
+    if pending.iter().any(|(file, _)| file.urls.len() > 1) {
+        let latencies =
+            mirrors::probe_origins(&client, pending.iter().flat_map(|(file, _)| &file.urls)).await;
+        for (file, _) in &mut pending {
+            mirrors::sort_by_latency(&mut file.urls, &latencies);
+        }
+    }
+
+    let semaphore = Arc::new(Semaphore::new(config.max_concurrent_downloads.max(1)));
+    let mut tasks = JoinSet::new();
+    let downloaded_files = pending.len();
+
+    for (file, dest) in pending {
+        let client = client.clone();
+        let config = config.clone();
+        let semaphore = semaphore.clone();
+        let control = control.clone();
+        let events = events.clone();
+        let instance_dir = instance_dir.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.map_err(|e| e.to_string())?;
+            control.checkpoint().await?;
+
+            if !store::is_shareable(&file.path) {
+                // Revérifié avant d'écrire : un lien a pu apparaître depuis `plan_sync`
+                paths::ensure_within(&instance_dir, &dest)
+                    .map_err(|e| format!("{}: {}", file.path, e))?;
+                return download_with_mirrors(&client, &config, &file, &dest, &control, &events)
+                    .await;
+            }
+
+            // Fichier partagé : téléchargé une seule fois dans le store, puis lié dans l'instance
+            let object = store::object_path(&file.sha1)?;
+            let _lock = store::lock_object(&file.sha1).await;
+            if store::is_valid_object(&object, &file.sha1).await {
+                let _ = events.send(SyncEvent::Progress { bytes: file.size });
+            } else {
+                download_with_mirrors(&client, &config, &file, &object, &control, &events).await?;
+            }
+            store::place(&object, &instance_dir, &dest).map_err(|e| format!("{}: {}", file.path, e))
+        });
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        let result = result
+            .map_err(|e| format!("Download task failed: {}", e))
+            .and_then(|r| r);
+        if let Err(e) = result {
+            // Arrête les autres téléchargements : le lancement échoue de toute façon
+            tasks.abort_all();
+            return Err(e);
+        }
+    }
+
+    for path in &removed {
+        paths::ensure_within(&instance_dir, path)?;
+        match std::fs::remove_file(path) {
+            Ok(()) => tracing::info!("Removed {:?}, no longer in the manifest", path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove '{}': {}", path.display(), e)),
+        }
+    }
+
+    if let Some(instance_id) = instance_dir.file_name().and_then(|name| name.to_str()) {
+        store::set_instance_refs(instance_id, shared_objects);
+    }
+
+    let _ = events.send(SyncEvent::Completed { downloaded_files });
+    Ok(())
+}
+
+/// Télécharge un fichier vers `dest` en essayant ses URLs dans l'ordre
+async fn download_with_mirrors(
+    client: &reqwest::Client,
+    config: &DownloadConfig,
+    file: &SyncFile,
+    dest: &Path,
+    control: &DownloadControl,
+    events: &mpsc::UnboundedSender<SyncEvent>,
+) -> Result<(), String> {
+    // Octets déjà remontés pour ce fichier : un changement de miroir ne les recompte pas
+    let mut reported: u64 = 0;
+    let mut errors = Vec::new();
+    for url in &file.urls {
+        let mut attempt_bytes: u64 = 0;
+        let result = download::download_file(
+            client,
+            config,
+            url,
+            dest,
+            Some(&file.sha1),
+            control,
+            |bytes| {
+                attempt_bytes += bytes;
+                if attempt_bytes > reported {
+                    let _ = events.send(SyncEvent::Progress {
+                        bytes: attempt_bytes - reported,
+                    });
+                    reported = attempt_bytes;
+                }
+            },
+        )
+        .await;
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if control.is_cancelled() => return Err(e),
+            Err(e) => {
+                tracing::warn!("{}: {}, trying next mirror", file.path, e);
+                errors.push(e);
+            }
+        }
+    }
+    Err(format!("{}: {}", file.path, errors.join("; ")))
+}
+
+/// Dossier d'une instance dans le dossier de données du launcher
+pub fn instance_dir(modpack_id: &str) -> PathBuf {
+    lighty_launcher::core::AppState::get_project_dirs()
+        .data_dir()
+        .join(modpack_id)
+}
+
+async fn is_up_to_date(dest: &Path, file: &SyncFile) -> bool {
+    let size_matches = tokio::fs::metadata(dest)
+        .await
+        .map(|m| file.size == 0 || m.len() == file.size)
+        .unwrap_or(false);
+    if !size_matches {
+        return false;
+    }
+
+    download::file_sha1(dest)
+        .await
+        .map(|sha1| sha1.eq_ignore_ascii_case(&file.sha1))
+        .unwrap_or(false)
+}
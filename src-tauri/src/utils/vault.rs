@@ -1,13 +1,16 @@
 use iota_stronghold::{KeyProvider, SnapshotPath};
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri::Manager;
 use tauri_plugin_stronghold::{kdf::KeyDerivation, stronghold::Stronghold};
 
+pub const VAULT_PASSWORD: &str = "dev-vault-password";
+
 pub struct VaultState {
     // On garde une instance en mémoire (ouverte) pour éviter de recharger à chaque commande
     pub inner: Mutex<Option<Stronghold>>,
-    pub app_handle: Mutex<Option<Arc<AppHandle>>>,
+    pub paths: Mutex<Option<VaultPaths>>,
     pub password: Mutex<Option<String>>,
 }
 
@@ -15,30 +18,69 @@ impl Default for VaultState {
     fn default() -> Self {
         Self {
             inner: Mutex::new(None),
-            app_handle: Mutex::new(None),
+            paths: Mutex::new(None),
             password: Mutex::new(None),
         }
     }
 }
 
+/// Emplacement du snapshot et du sel, résolu depuis l'app ou, sans interface, depuis
+/// les dossiers de données (voir `cli`)
+#[derive(Debug, Clone)]
+pub struct VaultPaths {
+    pub vault: PathBuf,
+    pub salt: PathBuf,
+}
+
+impl VaultPaths {
+    pub fn from_app(app: &AppHandle) -> Self {
+        Self::new(
+            &app.path().app_data_dir().expect("app_data_dir unavailable"),
+            &app.path()
+                .app_local_data_dir()
+                .expect("app_local_data_dir unavailable"),
+        )
+    }
+
+    /// `app_data_dir` / `app_local_data_dir` de Tauri
+    pub fn new(app_data_dir: &Path, app_local_data_dir: &Path) -> Self {
+        Self {
+            vault: app_data_dir.join("vault.hold"),
+            salt: app_local_data_dir.join("salt.txt"),
+        }
+    }
+}
+
 pub fn init_vault_if_needed(
     app: &AppHandle,
     state: &VaultState,
     password: &str,
 ) -> Result<(), String> {
+    init_vault(VaultPaths::from_app(app), state, password)
+}
+
+/// Ouvre le vault (ou le crée) ; sans `AppHandle`, utilisable en ligne de commande
+pub fn init_vault(paths: VaultPaths, state: &VaultState, password: &str) -> Result<(), String> {
     let mut guard = state.inner.lock().unwrap();
     if guard.is_some() {
         return Ok(());
     }
 
     // Toujours enregistrer le contexte pour permettre l'auto-réparation
-    *state.app_handle.lock().unwrap() = Some(Arc::new(app.clone()));
+    *state.paths.lock().unwrap() = Some(paths.clone());
     *state.password.lock().unwrap() = Some(password.to_string());
 
-    let v_path = vault_path(app);
+    let v_path = paths.vault.clone();
     tracing::info!("Vault path: {:?}", v_path);
 
-    let key = KeyDerivation::argon2(password, &salt_path(app));
+    // Sans interface, rien n'a encore créé les dossiers de données
+    for parent in [paths.vault.parent(), paths.salt.parent()]
+        .into_iter()
+        .flatten()
+    {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let key = KeyDerivation::argon2(password, &paths.salt);
     let key_provider = match KeyProvider::try_from(zeroize::Zeroizing::new(key.clone())) {
         Ok(kp) => kp,
         Err(e) => return Err(format!("Failed to create KeyProvider: {:?}", e)),
@@ -70,14 +112,14 @@ pub fn init_vault_if_needed(
                             Ok(new_sh) => {
                                 // On force la réinitialisation complète du state
                                 *guard = None;
-                                *state.app_handle.lock().unwrap() = None;
+                                *state.paths.lock().unwrap() = None;
                                 *state.password.lock().unwrap() = None;
                                 sh = new_sh;
                                 tracing::info!(
                                     "Nouveau vault.hold créé après suppression du corrompu"
                                 );
                                 // Réenregistrer le contexte après reset
-                                *state.app_handle.lock().unwrap() = Some(Arc::new(app.clone()));
+                                *state.paths.lock().unwrap() = Some(paths.clone());
                                 *state.password.lock().unwrap() = Some(password.to_string());
                             }
                             Err(e) => {
@@ -117,16 +159,16 @@ pub fn commit_snapshot(state: &VaultState) -> Result<(), String> {
     let guard = state.inner.lock().unwrap();
     let sh = guard.as_ref().ok_or("Vault not initialized")?;
 
-    let app_guard = state.app_handle.lock().unwrap();
-    let app = app_guard.as_ref().ok_or("App handle not set")?;
+    let paths_guard = state.paths.lock().unwrap();
+    let paths = paths_guard.as_ref().ok_or("Vault paths not set")?;
 
     let pwd_guard = state.password.lock().unwrap();
     let password = pwd_guard.as_ref().ok_or("Password not set")?;
 
-    let v_path = vault_path(app);
+    let v_path = &paths.vault;
     tracing::info!("Committing snapshot to: {:?}", v_path);
-    let snapshot_path = SnapshotPath::from_path(&v_path);
-    let key = KeyDerivation::argon2(password, &salt_path(app));
+    let snapshot_path = SnapshotPath::from_path(v_path);
+    let key = KeyDerivation::argon2(password, &paths.salt);
     let key_provider = KeyProvider::try_from(zeroize::Zeroizing::new(key))
         .map_err(|e| format!("Failed to create KeyProvider: {:?}", e))?;
 
@@ -148,11 +190,11 @@ where
     let mut guard = state.inner.lock().unwrap();
     if guard.is_none() {
         // Tentative d'auto-réparation du vault
-        let app_handle = state.app_handle.lock().unwrap();
-        let password = state.password.lock().unwrap();
-        if let (Some(app), Some(pwd)) = (app_handle.as_ref(), password.as_ref()) {
+        let paths = state.paths.lock().unwrap().clone();
+        let password = state.password.lock().unwrap().clone();
+        if let (Some(paths), Some(pwd)) = (paths, password) {
             drop(guard);
-            match init_vault_if_needed(app, state, pwd) {
+            match init_vault(paths, state, &pwd) {
                 Ok(_) => {
                     guard = state.inner.lock().unwrap();
                 }
@@ -169,17 +211,3 @@ where
         .ok_or("Vault not initialized (auto-repair failed)")?;
     f(sh)
 }
-
-fn vault_path(app: &AppHandle) -> std::path::PathBuf {
-    app.path()
-        .app_data_dir()
-        .expect("app_data_dir unavailable")
-        .join("vault.hold")
-}
-
-fn salt_path(app: &AppHandle) -> std::path::PathBuf {
-    app.path()
-        .app_local_data_dir()
-        .expect("app_local_data_dir unavailable")
-        .join("salt.txt")
-}
@@ -0,0 +1,102 @@
+import { computed, ref } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { LightyEvent, type DeepLinkPayload } from '../types/lighty-events'
+import { useModpacksCommand } from './useModpacksCommand'
+import { useLauncherStore } from '../stores/launcherStore'
+import { useErrorStore } from '../stores/errorStore'
+
+const show = ref(false)
+const pendingLink = ref<DeepLinkPayload | null>(null)
+let unlisten: UnlistenFn | null = null
+
+/**
+ * Liens `miratopia://` (site, Discord) : rien n'est exécuté sans confirmation du joueur.
+ */
+export function useDeepLinkConfirm() {
+  const { startModpack } = useModpacksCommand()
+  const launcher = useLauncherStore()
+  const errorStore = useErrorStore()
+
+  const message = computed(() => {
+    const link = pendingLink.value
+    if (!link) return ''
+    switch (link.action) {
+      case 'launch':
+        return `Un lien demande le lancement du modpack ${link.target}. Voulez-vous le lancer ?`
+      case 'install':
+        return `Un lien demande l'installation du modpack ${link.target}. Voulez-vous l'installer ?`
+      case 'server':
+        return `Un lien vous invite sur le serveur ${link.target}. Voulez-vous lancer le modpack correspondant ?`
+    }
+  })
+
+  const confirmLabel = computed(() => {
+    switch (pendingLink.value?.action) {
+      case 'install':
+        return 'Installer'
+      case 'server':
+        return 'Rejoindre'
+      default:
+        return 'Lancer'
+    }
+  })
+
+  async function takePending() {
+    const link = await invoke<DeepLinkPayload | null>('take_pending_deep_link')
+    if (link) {
+      pendingLink.value = link
+      show.value = true
+    }
+  }
+
+  async function setup() {
+    // Seule la fenêtre principale traite les liens (la console partage le layout)
+    if (unlisten || getCurrentWindow().label !== 'main') return
+    unlisten = await listen<DeepLinkPayload>(LightyEvent.DeepLink, () => takePending())
+    // Lien reçu au démarrage, avant l'écoute
+    await takePending()
+  }
+
+  async function confirm() {
+    const link = pendingLink.value
+    show.value = false
+    pendingLink.value = null
+    if (!link) return
+
+    try {
+      if (link.action === 'launch') {
+        await startModpack(link.target)
+      } else if (link.action === 'install') {
+        await invoke('install_modpack', { modpackName: link.target })
+      } else if (link.action === 'server') {
+        await joinServer(link.target)
+      }
+    } catch (error) {
+      console.error('Failed to handle deep link:', error)
+    }
+  }
+
+  /** Lance le modpack qui déclare ce serveur dans son manifest */
+  async function joinServer(address: string) {
+    const modpack = await invoke<string | null>('find_server_modpack', { address })
+    if (!modpack) {
+      errorStore.setError({
+        category: 'deep-link',
+        message: `Aucun modpack disponible pour le serveur ${address}`,
+        timestamp: Date.now(),
+      })
+      return
+    }
+    launcher.selectedPack = modpack
+    await startModpack(modpack)
+  }
+
+  function cancel() {
+    show.value = false
+    pendingLink.value = null
+  }
+
+  return { show, pendingLink, message, confirmLabel, setup, confirm, cancel }
+}
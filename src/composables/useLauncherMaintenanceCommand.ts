@@ -38,10 +38,31 @@ export function useLauncherMaintenanceCommand() {
     }
   }
 
+  /** Réglages de téléchargement modifiés, appliqués à Java/librairies/assets au redémarrage */
+  async function isRestartRequired(): Promise<boolean> {
+    try {
+      return await invoke<boolean>('is_restart_required')
+    } catch (error) {
+      consola.error('Failed to check pending restart:', error)
+      return false
+    }
+  }
+
+  async function restartLauncher(): Promise<void> {
+    try {
+      await invoke('restart_launcher')
+    } catch (error) {
+      consola.error('Failed to restart launcher:', error)
+      throw error
+    }
+  }
+
   return {
     clearCache,
     resetAllSettings,
     clearAllAccounts,
     deleteAllModpacks,
+    isRestartRequired,
+    restartLauncher,
   }
 }
@@ -1,4 +1,5 @@
 import { onMounted, onUnmounted } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import { useConsoleStore } from '../stores/consoleStore'
 import { useDownloadStore } from '../stores/downloadStore'
@@ -39,6 +40,11 @@ export function useLightyEvents() {
           if (event.payload.status === LaunchStatus.Exited || event.payload.status === LaunchStatus.Failed) {
             launcherStore.launching = false
           }
+
+          // Session enregistrée à la sortie du jeu
+          if (event.payload.status === LaunchStatus.Exited) {
+            launcherStore.fetchPlayStats()
+          }
         }
       )
 
@@ -64,6 +70,10 @@ export function useLightyEvents() {
         unlistenConsole,
         unlistenError,
       ]
+
+      // Sortie émise avant l'ouverture de cette fenêtre (console ouverte en cours de partie)
+      const history = await invoke<ConsoleLinePayload[]>('get_console_history')
+      consoleStore.replayLogs(history)
     } catch (error) {
       console.error('Failed to listen to download progress:', error)
       throw error
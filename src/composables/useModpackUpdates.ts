@@ -0,0 +1,31 @@
+import { ref } from 'vue'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { LightyEvent, type ModpackUpdatePayload } from '../types/lighty-events'
+import { useModpacksCommand } from './useModpacksCommand'
+
+/** Mises à jour disponibles, par nom de modpack */
+const updates = ref<Record<string, ModpackUpdatePayload>>({})
+let unlisten: UnlistenFn | null = null
+
+/**
+ * Mises à jour des modpacks installés, vérifiées par le backend au démarrage.
+ */
+export function useModpackUpdates() {
+  const { takePendingModpackUpdates } = useModpacksCommand()
+
+  async function takePending() {
+    for (const update of await takePendingModpackUpdates()) {
+      updates.value[update.modpack_name] = update
+    }
+  }
+
+  async function setup() {
+    if (unlisten || getCurrentWindow().label !== 'main') return
+    unlisten = await listen<ModpackUpdatePayload>(LightyEvent.ModpackUpdateAvailable, () => takePending())
+    // Vérification terminée avant l'écoute
+    await takePending()
+  }
+
+  return { updates, setup }
+}
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core"
+import type { ModpackPlayStats, ModpackUpdatePayload, PlaySession } from "../types/lighty-events"
 
 export function useModpacksCommand() {
   async function listModpacks(): Promise<any> {
@@ -49,11 +50,41 @@ export function useModpacksCommand() {
     }
   }
 
+  async function getPlayStats(): Promise<ModpackPlayStats[]> {
+    try {
+      return await invoke<ModpackPlayStats[]>('get_play_stats')
+    } catch (error) {
+      console.error('Failed to get play stats:', error)
+      return []
+    }
+  }
+
+  async function getRecentSessions(limit?: number): Promise<PlaySession[]> {
+    try {
+      return await invoke<PlaySession[]>('get_recent_sessions', { limit })
+    } catch (error) {
+      console.error('Failed to get recent sessions:', error)
+      return []
+    }
+  }
+
+  async function takePendingModpackUpdates(): Promise<ModpackUpdatePayload[]> {
+    try {
+      return await invoke<ModpackUpdatePayload[]>('take_pending_modpack_updates')
+    } catch (error) {
+      console.error('Failed to get modpack updates:', error)
+      return []
+    }
+  }
+
   return {
     listModpacks,
     startModpack,
     stopModpack,
     openModpacksFolder,
     openLauncherFolder,
+    getPlayStats,
+    getRecentSessions,
+    takePendingModpackUpdates,
   }
 }
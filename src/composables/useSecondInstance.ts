@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import consola from 'consola'
+import { LightyEvent, type SecondInstancePayload } from '../types/lighty-events'
+import { useLauncherStore } from '../stores/launcherStore'
+
+let unlisten: UnlistenFn | null = null
+
+/**
+ * Arguments d'un second lancement du launcher (raccourci, ligne de commande) :
+ * sélection du modpack, ouverture de la console, fichiers à importer.
+ */
+export function useSecondInstance() {
+  const store = useLauncherStore()
+
+  async function handle(payload: SecondInstancePayload) {
+    consola.info('Second instance arguments:', payload)
+
+    if (payload.modpack) {
+      if (store.modpacks.some((pack) => pack.id === payload.modpack)) {
+        store.selectedPack = payload.modpack
+      } else {
+        consola.warn(`Unknown modpack from second instance: ${payload.modpack}`)
+      }
+    }
+
+    if (payload.console) {
+      await invoke('open_console_window').catch((error) => {
+        console.error('Failed to open console window:', error)
+      })
+    }
+
+    if (payload.files.length > 0) {
+      // Aucun format d'import n'est encore pris en charge
+      consola.warn('Files to import are not supported yet:', payload.files)
+    }
+  }
+
+  async function setup() {
+    if (unlisten || getCurrentWindow().label !== 'main') return
+    unlisten = await listen<SecondInstancePayload>(LightyEvent.SecondInstance, (event) => handle(event.payload))
+  }
+
+  return { setup }
+}
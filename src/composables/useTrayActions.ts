@@ -0,0 +1,40 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import consola from 'consola'
+import { LightyEvent } from '../types/lighty-events'
+import { useAccountsStore } from '../stores/accountsStore'
+import { useLauncherStore } from '../stores/launcherStore'
+
+let unlisten: UnlistenFn[] = []
+
+/**
+ * Actions du menu de la barre des tâches : lancement rapide et changement de compte.
+ */
+export function useTrayActions() {
+  const launcher = useLauncherStore()
+  const accounts = useAccountsStore()
+
+  async function launch(modpackName: string) {
+    if (launcher.launching) {
+      consola.warn(`Tray launch ignored, a launch is already in progress: ${modpackName}`)
+      return
+    }
+    launcher.selectedPack = modpackName
+    await launcher.launchGame()
+  }
+
+  async function accountChanged() {
+    await accounts.fetchActiveAccount()
+    await accounts.refreshLauncherData()
+  }
+
+  async function setup() {
+    if (unlisten.length > 0 || getCurrentWindow().label !== 'main') return
+    unlisten = [
+      await listen<string>(LightyEvent.TrayLaunch, (event) => launch(event.payload)),
+      await listen<string>(LightyEvent.ActiveAccountChanged, () => accountChanged()),
+    ]
+  }
+
+  return { setup }
+}
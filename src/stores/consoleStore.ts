@@ -20,6 +20,29 @@ export const useConsoleStore = defineStore('console', {
 
       return allLogs
     },
+
+    /** Instances ayant produit des logs, dans l'ordre d'apparition */
+    instances: (state): string[] => {
+      const names = new Set<string>()
+
+      state.logs.forEach((logs) => {
+        if (logs.length > 0) names.add(logs[0].instance_name)
+      })
+
+      return [...names]
+    },
+
+    getLogsByInstance: (state) => {
+      return (instanceName: string): ConsoleLinePayload[] => {
+        const logs: ConsoleLinePayload[] = []
+
+        state.logs.forEach((pidLogs) => {
+          if (pidLogs[0]?.instance_name === instanceName) logs.push(...pidLogs)
+        })
+
+        return logs
+      }
+    },
   },
 
   actions: {
@@ -31,6 +54,24 @@ export const useConsoleStore = defineStore('console', {
       this.logs.get(pid)!.push(log)
     },
 
+    /**
+     * Ajoute la sortie déjà émise avant l'ouverture de la fenêtre : seules les lignes
+     * antérieures à celles reçues en direct sont gardées, pour éviter les doublons.
+     */
+    replayLogs(history: ConsoleLinePayload[]): void {
+      const replayed = new Map<number, ConsoleLinePayload[]>()
+      for (const log of history) {
+        const live = this.logs.get(log.pid)
+        if (live?.length && log.timestamp >= live[0].timestamp) continue
+        if (!replayed.has(log.pid)) replayed.set(log.pid, [])
+        replayed.get(log.pid)!.push(log)
+      }
+
+      replayed.forEach((logs, pid) => {
+        this.logs.set(pid, [...logs, ...(this.logs.get(pid) ?? [])])
+      })
+    },
+
     clearLogs(pid: number): void {
       this.logs.delete(pid)
     },
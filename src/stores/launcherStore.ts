@@ -4,7 +4,7 @@ import { useSettingsCommand } from '../composables/useSettingsCommand'
 import { useAccountsStore } from './accountsStore'
 import { useLaunchStore } from './launchStore'
 import { useDownloadStore } from './downloadStore'
-import { LaunchStatus } from '../types/lighty-events'
+import { LaunchStatus, type ModpackPlayStats } from '../types/lighty-events'
 import type { Settings } from '../types/settings'
 import consola from 'consola'
 
@@ -41,6 +41,7 @@ export const useLauncherStore = defineStore('launcher', {
 
     modpacks: [] as Modpack[],
     modpacksLoading: false,
+    playStats: [] as ModpackPlayStats[],
 
     modpackSettings: null as Settings | null,
     modpackSettingsLoading: false,
@@ -56,6 +57,16 @@ export const useLauncherStore = defineStore('launcher', {
       return this.modpacks.find((p) => p.id === this.selectedPack)
     },
 
+    /** Modpacks joués le plus récemment d'abord, puis ceux jamais joués */
+    modpacksByRecentUse(): Modpack[] {
+      // Les stats sont par instance ; `pack.id` est le nom du modpack dans launcher.json
+      const lastPlayed = (name: string) =>
+        this.playStats
+          .filter((stats) => (stats.modpack_name ?? stats.modpack) === name)
+          .reduce((last, stats) => Math.max(last, stats.last_played), 0)
+      return [...this.modpacks].sort((a, b) => lastPlayed(b.id) - lastPlayed(a.id))
+    },
+
     activeSettingsModpackId(): string | null {
       if (this.settingsTab.startsWith('modpack:')) {
         return this.settingsTab.slice('modpack:'.length)
@@ -154,8 +165,14 @@ export const useLauncherStore = defineStore('launcher', {
       }
     },
 
+    async fetchPlayStats() {
+      const { getPlayStats } = useModpacksCommand()
+      this.playStats = await getPlayStats()
+    },
+
     async fetchModpacks() {
       const { listModpacks } = useModpacksCommand()
+      await this.fetchPlayStats()
       try {
         this.modpacksLoading = true
         const result = await listModpacks()
@@ -1,8 +1,14 @@
 export enum LightyEvent {
   DownloadProgress = 'lighty://download-progress',
   LaunchStatus = 'lighty://launch-status',
+  LaunchProgress = 'lighty://launch-progress',
   ConsoleOutput = 'lighty://console-output',
   Error = 'lighty://error',
+  ModpackUpdateAvailable = 'lighty://modpack-update-available',
+  DeepLink = 'lighty://deep-link',
+  SecondInstance = 'lighty://second-instance',
+  TrayLaunch = 'lighty://tray-launch',
+  ActiveAccountChanged = 'lighty://active-account-changed',
 }
 
 export interface LaunchStatusPayload {
@@ -20,6 +26,7 @@ export enum LaunchStatus {
   Installing = "installing",
   Downloading = "downloading",
   Failed = "failed",
+  Cancelled = "cancelled",
 }
 
 export interface DownloadProgressPayload {
@@ -32,9 +39,32 @@ export interface DownloadProgressPayload {
 }
 
 export enum DownloadPhase {
+  Idle = "idle",
+  Modpack = "modpack",
   Java = "java",
-  Extracting = "extracting",
+  Loader = "loader",
   Game = "game",
+  Extracting = "extracting",
+}
+
+export interface PhaseProgress {
+  phase: DownloadPhase,
+  current: number,
+  total: number,
+  percentage: number,
+  weight: number,
+  completed: boolean,
+}
+
+export interface LaunchProgressPayload {
+  instance_name: string,
+  current_phase: DownloadPhase,
+  overall_percentage: number,
+  phases: PhaseProgress[],
+  completed_phases: DownloadPhase[],
+  bytes_per_second: number,
+  eta_seconds?: number,
+  message: string,
 }
 
 export enum StdStream {
@@ -42,12 +72,25 @@ export enum StdStream {
   Stderr = "stderr",
 }
 
+export enum ConsoleLevel {
+  Trace = "trace",
+  Debug = "debug",
+  Info = "info",
+  Warn = "warn",
+  Error = "error",
+  Fatal = "fatal",
+}
+
 export interface ConsoleLinePayload {
   instance_name: string,
   pid: number,
   stream: StdStream,
   line: string,
   timestamp: number,
+  level: ConsoleLevel,
+  thread?: string,
+  logger?: string,
+  message: string,
 }
 
 export interface ErrorPayload {
@@ -56,3 +99,83 @@ export interface ErrorPayload {
   details?: string,
   timestamp: number,
 }
+
+export interface FileChange {
+  path: string,
+  old_hash?: string,
+  new_hash?: string,
+}
+
+export interface VersionChange {
+  from?: string,
+  to?: string,
+}
+
+export interface ModpackUpdatePayload {
+  modpack_name: string,
+  modpack_id: string,
+  installed: boolean,
+  has_update: boolean,
+  mods_added: FileChange[],
+  mods_removed: FileChange[],
+  mods_updated: FileChange[],
+  other_files_changed: number,
+  loader_change?: VersionChange,
+  minecraft_change?: VersionChange,
+  changelog?: string,
+}
+
+export type ManifestSeverity = 'error' | 'warning'
+
+export interface ManifestIssue {
+  severity: ManifestSeverity,
+  entry?: string,
+  message: string,
+}
+
+export interface ManifestReport {
+  schema_version: number,
+  valid: boolean,
+  error_count: number,
+  warning_count: number,
+  issues: ManifestIssue[],
+}
+
+export type DeepLinkAction = 'launch' | 'install' | 'server'
+
+export interface DeepLinkPayload {
+  action: DeepLinkAction,
+  target: string,
+  url: string,
+}
+
+export interface SecondInstancePayload {
+  modpack?: string,
+  console: boolean,
+  files: string[],
+  cwd: string,
+}
+
+export interface PlaySession {
+  /** Identifiant de l'instance (`manifest.id`) */
+  modpack: string,
+  /** Nom du modpack dans `launcher.json` */
+  modpack_name?: string,
+  account?: string,
+  /** Secondes depuis l'epoch */
+  started_at: number,
+  ended_at: number,
+  exit_code?: number,
+  crashed: boolean,
+}
+
+export interface ModpackPlayStats {
+  /** Identifiant de l'instance (`manifest.id`) */
+  modpack: string,
+  /** Nom du modpack dans `launcher.json` */
+  modpack_name?: string,
+  total_seconds: number,
+  session_count: number,
+  /** Fin de la dernière session, secondes depuis l'epoch */
+  last_played: number,
+}
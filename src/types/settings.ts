@@ -78,3 +78,46 @@ export const DEFAULT_SETTINGS: Settings = {
   windowWidth: 1280,
   windowHeight: 720,
 }
+
+/**
+ * Paramètres globaux du launcher (communs à tous les modpacks).
+ *
+ * Même convention que `Settings` : un champ `undefined` n'est pas défini.
+ */
+export interface LauncherSettings {
+  logLevel?: string,
+  /**
+   * Avec `maxRetries` et `retryDelayMs` : appliqué aussitôt aux fichiers des modpacks,
+   * au prochain démarrage pour Java, les librairies et les assets (`is_restart_required`)
+   */
+  maxConcurrentDownloads?: number,
+  maxRetries?: number,
+  retryDelayMs?: number,
+  /** Octets par seconde, `0` ou `undefined` : illimité. Fichiers des modpacks seulement */
+  bandwidthLimit?: number,
+  /**
+   * `http://`, `https://` ou `socks5://`, utilisé par les requêtes du launcher
+   * (pas par les téléchargements de Java, des librairies et des assets)
+   */
+  proxyUrl?: string,
+  /** Chemins de fichiers PEM de certificats racines supplémentaires */
+  caCertificates?: string[],
+  connectTimeoutSecs?: number,
+  requestTimeoutSecs?: number,
+  /** Fenêtre principale au démarrage du jeu */
+  onGameStart?: GameStartBehavior,
+  /** Réafficher la fenêtre principale quand le jeu se ferme */
+  restoreOnGameExit?: boolean,
+}
+
+/**
+ * Comportement de la fenêtre principale quand le jeu démarre.
+ *
+ * `Close` ne quitte le launcher qu'à la fin du jeu, pour ne jamais l'orpheliner.
+ */
+export enum GameStartBehavior {
+  KeepOpen = "keep_open",
+  Minimize = "minimize",
+  HideToTray = "hide_to_tray",
+  Close = "close",
+}